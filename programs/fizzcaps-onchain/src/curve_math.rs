@@ -0,0 +1,395 @@
+// ============ CURVE MATH: u128 CONSTANT-PRODUCT QUOTES ============
+//
+// Reserves are u64 lamports / token base units, but their product is not:
+// 30e9 virtual lamports x 8e17 tokens is ~2.4e28, far past u64::MAX. Every
+// intermediate here is u128, and every rounding step goes in the pool's
+// favor: amounts paid out round down, fees charged round up.
+//
+// Pure functions only, so tests and off-chain clients quote exactly what
+// the program will execute.
+
+use crate::FizzError;
+
+/// Basis-point denominator for fees
+pub const BPS_DENOMINATOR: u64 = 10_000;
+/// Base units per whole launchpad token (9 decimals)
+pub const TOKEN_UNIT: u64 = 1_000_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CurveMathError {
+    Overflow,
+}
+
+impl From<CurveMathError> for FizzError {
+    fn from(_: CurveMathError) -> Self {
+        FizzError::MathOverflow
+    }
+}
+
+/// Bonding-curve state a quote needs
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CurveReserves {
+    /// Real SOL deposited by buyers
+    pub sol_reserve: u64,
+    /// SOL added on top of `sol_reserve` to set the starting price
+    pub virtual_sol: u64,
+    /// Tokens left in the curve
+    pub token_reserve: u64,
+}
+
+impl CurveReserves {
+    /// Real plus virtual SOL, the curve's SOL side
+    pub fn effective_sol(&self) -> Result<u64, CurveMathError> {
+        self.sol_reserve
+            .checked_add(self.virtual_sol)
+            .ok_or(CurveMathError::Overflow)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BuyQuote {
+    /// Taken from `sol_amount` before it reaches the curve
+    pub fee: u64,
+    /// SOL added to `sol_reserve`
+    pub sol_in: u64,
+    pub tokens_out: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SellQuote {
+    /// SOL removed from `sol_reserve`
+    pub sol_out_gross: u64,
+    pub fee: u64,
+    /// SOL the seller receives
+    pub sol_out: u64,
+}
+
+/// How a pool swap fee is shared out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FeeSplit {
+    pub treasury: u64,
+    pub buyback: u64,
+    /// Left in the pool for liquidity providers
+    pub lp: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DepositQuote {
+    pub tokens_in: u64,
+    pub lp_out: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WithdrawalQuote {
+    pub sol_out: u64,
+    pub tokens_out: u64,
+}
+
+fn to_u64(value: u128) -> Result<u64, CurveMathError> {
+    u64::try_from(value).map_err(|_| CurveMathError::Overflow)
+}
+
+/// `a * b / c`, rounded down
+fn mul_div(a: u64, b: u64, c: u64) -> Result<u64, CurveMathError> {
+    let out = (a as u128 * b as u128)
+        .checked_div(c as u128)
+        .ok_or(CurveMathError::Overflow)?;
+    to_u64(out)
+}
+
+/// `a * b / c`, rounded up
+fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64, CurveMathError> {
+    if c == 0 {
+        return Err(CurveMathError::Overflow);
+    }
+    to_u64((a as u128 * b as u128).div_ceil(c as u128))
+}
+
+/// Fee on `amount`, rounded up
+pub fn fee(amount: u64, fee_bps: u64) -> Result<u64, CurveMathError> {
+    let fee = (amount as u128 * fee_bps as u128).div_ceil(BPS_DENOMINATOR as u128);
+    to_u64(fee)
+}
+
+/// Constant-product output for `amount_in`, rounded down:
+/// `reserve_out * amount_in / (reserve_in + amount_in)`
+pub fn swap_out(reserve_in: u64, reserve_out: u64, amount_in: u64) -> Result<u64, CurveMathError> {
+    let denominator = reserve_in as u128 + amount_in as u128;
+    let out = (reserve_out as u128 * amount_in as u128)
+        .checked_div(denominator)
+        .ok_or(CurveMathError::Overflow)?;
+    to_u64(out)
+}
+
+/// Tokens bought for `sol_amount` lamports, fee included
+pub fn quote_buy(
+    reserves: CurveReserves,
+    sol_amount: u64,
+    fee_bps: u64,
+) -> Result<BuyQuote, CurveMathError> {
+    let fee = fee(sol_amount, fee_bps)?;
+    let sol_in = sol_amount.checked_sub(fee).ok_or(CurveMathError::Overflow)?;
+    let tokens_out = swap_out(reserves.effective_sol()?, reserves.token_reserve, sol_in)?;
+    Ok(BuyQuote {
+        fee,
+        sol_in,
+        tokens_out,
+    })
+}
+
+/// SOL received for `token_amount`, capped at the real SOL in the curve
+pub fn quote_sell(
+    reserves: CurveReserves,
+    token_amount: u64,
+    fee_bps: u64,
+) -> Result<SellQuote, CurveMathError> {
+    let sol_out_gross = swap_out(reserves.token_reserve, reserves.effective_sol()?, token_amount)?
+        .min(reserves.sol_reserve);
+    let fee = fee(sol_out_gross, fee_bps)?;
+    Ok(SellQuote {
+        sol_out_gross,
+        fee,
+        sol_out: sol_out_gross - fee,
+    })
+}
+
+/// Share a swap fee: protocol cuts round down, LPs keep the remainder
+pub fn split_fee(
+    fee: u64,
+    treasury_share_bps: u64,
+    buyback_share_bps: u64,
+) -> Result<FeeSplit, CurveMathError> {
+    let treasury = mul_div(fee, treasury_share_bps, BPS_DENOMINATOR)?;
+    let buyback = mul_div(fee, buyback_share_bps, BPS_DENOMINATOR)?;
+    let lp = fee
+        .checked_sub(treasury)
+        .and_then(|rest| rest.checked_sub(buyback))
+        .ok_or(CurveMathError::Overflow)?;
+    Ok(FeeSplit {
+        treasury,
+        buyback,
+        lp,
+    })
+}
+
+/// Tokens owed (rounded up) and LP minted (rounded down) for depositing
+/// `sol_amount` into a pool at its current ratio
+pub fn quote_deposit(
+    reserves: CurveReserves,
+    lp_supply: u64,
+    sol_amount: u64,
+) -> Result<DepositQuote, CurveMathError> {
+    Ok(DepositQuote {
+        tokens_in: mul_div_ceil(sol_amount, reserves.token_reserve, reserves.sol_reserve)?,
+        lp_out: mul_div(sol_amount, lp_supply, reserves.sol_reserve)?,
+    })
+}
+
+/// Pro-rata SOL and tokens for burning `lp_amount`, both rounded down
+pub fn quote_withdrawal(
+    reserves: CurveReserves,
+    lp_supply: u64,
+    lp_amount: u64,
+) -> Result<WithdrawalQuote, CurveMathError> {
+    Ok(WithdrawalQuote {
+        sol_out: mul_div(lp_amount, reserves.sol_reserve, lp_supply)?,
+        tokens_out: mul_div(lp_amount, reserves.token_reserve, lp_supply)?,
+    })
+}
+
+/// Marginal price in lamports per whole token, rounded down
+pub fn spot_price(reserves: CurveReserves) -> Result<u64, CurveMathError> {
+    let price = (reserves.effective_sol()? as u128 * TOKEN_UNIT as u128)
+        .checked_div(reserves.token_reserve as u128)
+        .ok_or(CurveMathError::Overflow)?;
+    to_u64(price)
+}
+
+/// `total_supply` valued at the spot price, in lamports, rounded down
+pub fn market_cap(reserves: CurveReserves, total_supply: u64) -> Result<u64, CurveMathError> {
+    let cap = (reserves.effective_sol()? as u128 * total_supply as u128)
+        .checked_div(reserves.token_reserve as u128)
+        .ok_or(CurveMathError::Overflow)?;
+    to_u64(cap)
+}
+
+/// Integer square root, rounded down
+pub fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    // Newton's method from an over-estimate converges downward
+    let mut x = 1u128 << (128 - value.leading_zeros()).div_ceil(2);
+    loop {
+        let y = (x + value / x) / 2;
+        if y >= x {
+            return x;
+        }
+        x = y;
+    }
+}
+
+/// LP minted when a pool is seeded: `sqrt(sol * tokens)`, rounded down
+pub fn initial_lp(sol_amount: u64, token_amount: u64) -> Result<u64, CurveMathError> {
+    to_u64(isqrt(sol_amount as u128 * token_amount as u128))
+}
+
+/// Tokens a curve should sell so the pool seeded at graduation opens at the
+/// curve's closing price, rounded down.
+///
+/// Selling `S` tokens for exactly `graduation_sol` leaves
+/// `S * virtual_sol / (virtual_sol + graduation_sol)` in the curve, a closing
+/// price of `(virtual_sol + graduation_sol)^2 / (S * virtual_sol)`. The pool
+/// gets `graduation_sol` less the creator bonus against the
+/// `total_supply - S` tokens the curve never held. Equating the two:
+/// `S = total_supply * (v + g)^2 / ((v + g)^2 + g * v * (1 - bonus))`.
+pub fn graduation_curve_supply(
+    total_supply: u64,
+    virtual_sol: u64,
+    graduation_sol: u64,
+    creator_bonus_bps: u64,
+) -> Result<u64, CurveMathError> {
+    let pool_bps = BPS_DENOMINATOR
+        .checked_sub(creator_bonus_bps)
+        .ok_or(CurveMathError::Overflow)?;
+    let closing_sol = virtual_sol as u128 + graduation_sol as u128;
+    let mut curve_weight = closing_sol
+        .checked_mul(closing_sol)
+        .and_then(|sol| sol.checked_mul(BPS_DENOMINATOR as u128))
+        .ok_or(CurveMathError::Overflow)?;
+    let mut pool_weight = (graduation_sol as u128 * virtual_sol as u128)
+        .checked_mul(pool_bps as u128)
+        .ok_or(CurveMathError::Overflow)?;
+    // Only the ratio matters; drop low bits until the sum fits in a u64
+    while curve_weight + pool_weight > u64::MAX as u128 {
+        curve_weight >>= 1;
+        pool_weight >>= 1;
+    }
+    mul_div(total_supply, to_u64(curve_weight)?, to_u64(curve_weight + pool_weight)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAUNCH: CurveReserves = CurveReserves {
+        sol_reserve: 0,
+        virtual_sol: 30_000_000_000,
+        token_reserve: 800_000_000_000_000_000,
+    };
+
+    #[test]
+    fn first_trade_does_not_overflow() {
+        let quote = quote_buy(LAUNCH, 1_000_000_000, 100).unwrap();
+        assert_eq!(quote.fee, 10_000_000);
+        assert_eq!(quote.sol_in, 990_000_000);
+        // 8e17 * 0.99e9 / 30.99e9, rounded down
+        assert_eq!(quote.tokens_out, 25_556_631_171_345_595);
+    }
+
+    #[test]
+    fn rounds_in_pool_favor() {
+        assert_eq!(fee(1, 100).unwrap(), 1);
+        assert_eq!(fee(10_000, 100).unwrap(), 100);
+        assert_eq!(swap_out(3, 10, 1).unwrap(), 2);
+
+        // A round trip never returns more SOL than went in
+        let buy = quote_buy(LAUNCH, 5_000_000_000, 0).unwrap();
+        let after_buy = CurveReserves {
+            sol_reserve: buy.sol_in,
+            token_reserve: LAUNCH.token_reserve - buy.tokens_out,
+            ..LAUNCH
+        };
+        let sell = quote_sell(after_buy, buy.tokens_out, 0).unwrap();
+        assert!(sell.sol_out <= buy.sol_in);
+    }
+
+    #[test]
+    fn sell_is_capped_at_real_sol() {
+        let reserves = CurveReserves {
+            sol_reserve: 1_000,
+            ..LAUNCH
+        };
+        let quote = quote_sell(reserves, 100_000_000_000_000_000, 100).unwrap();
+        assert_eq!(quote.sol_out_gross, 1_000);
+        assert_eq!(quote.fee, 10);
+        assert_eq!(quote.sol_out, 990);
+    }
+
+    #[test]
+    fn prices_the_curve() {
+        // 30 SOL / 800M tokens = 37.5 lamports per token
+        assert_eq!(spot_price(LAUNCH).unwrap(), 37);
+        assert_eq!(market_cap(LAUNCH, 1_000_000_000_000_000_000).unwrap(), 37_500_000_000);
+        let empty = CurveReserves {
+            token_reserve: 0,
+            ..LAUNCH
+        };
+        assert_eq!(spot_price(empty), Err(CurveMathError::Overflow));
+    }
+
+    #[test]
+    fn splits_fees_and_liquidity() {
+        let split = split_fee(1_001, 2_000, 2_000).unwrap();
+        assert_eq!(split, FeeSplit { treasury: 200, buyback: 200, lp: 601 });
+        assert_eq!(split_fee(100, 6_000, 6_000), Err(CurveMathError::Overflow));
+
+        let pool = CurveReserves {
+            sol_reserve: 3_000,
+            virtual_sol: 0,
+            token_reserve: 1_000,
+        };
+        let deposit = quote_deposit(pool, 500, 1_000).unwrap();
+        assert_eq!(deposit, DepositQuote { tokens_in: 334, lp_out: 166 });
+        let withdrawal = quote_withdrawal(pool, 500, 166).unwrap();
+        assert_eq!(withdrawal, WithdrawalQuote { sol_out: 996, tokens_out: 332 });
+        assert_eq!(quote_withdrawal(pool, 0, 1), Err(CurveMathError::Overflow));
+    }
+
+    #[test]
+    fn square_roots() {
+        assert_eq!(isqrt(0), 0);
+        assert_eq!(isqrt(15), 3);
+        assert_eq!(isqrt(16), 4);
+        assert_eq!(isqrt(u128::MAX), u64::MAX as u128);
+        // 79 SOL against 200M tokens
+        assert_eq!(
+            initial_lp(79_000_000_000, 200_000_000_000_000_000).unwrap(),
+            125_698_050_899_765
+        );
+    }
+
+    #[test]
+    fn graduated_pool_opens_at_the_closing_price() {
+        let total_supply = 1_000_000_000 * TOKEN_UNIT;
+        for (virtual_sol, graduation_sol) in [
+            (30_000_000_000, 85_000_000_000),
+            (1_000_000_000, 10_000_000_000),
+            (100_000_000_000, 500_000_000_000),
+        ] {
+            for creator_bonus_bps in [0, 700, 2_000] {
+                let curve_supply = graduation_curve_supply(
+                    total_supply,
+                    virtual_sol,
+                    graduation_sol,
+                    creator_bonus_bps,
+                )
+                .unwrap();
+                let sold = swap_out(virtual_sol, curve_supply, graduation_sol).unwrap();
+                let bonus = mul_div(graduation_sol, creator_bonus_bps, BPS_DENOMINATOR).unwrap();
+                let pool_sol = graduation_sol - bonus;
+                // Closing (v + g) / unsold against opening pool_sol / (total - curve),
+                // cross-multiplied to keep the comparison exact
+                let closing = (virtual_sol + graduation_sol) as u128
+                    * (total_supply - curve_supply) as u128;
+                let opening = pool_sol as u128 * (curve_supply - sold) as u128;
+                // Within 0.1%
+                assert!(closing.abs_diff(opening) * 1_000 <= closing, "{closing} vs {opening}");
+            }
+        }
+        assert_eq!(
+            graduation_curve_supply(total_supply, 1, 1, BPS_DENOMINATOR + 1),
+            Err(CurveMathError::Overflow)
+        );
+    }
+}
@@ -0,0 +1,417 @@
+// ============ ED25519 PRECOMPILE PARSER ============
+//
+// Instruction data layout of the native Ed25519 program:
+//
+//   [0]      num_signatures: u8
+//   [1]      padding: u8
+//   [2..]    num_signatures x 14-byte offset records:
+//              signature_offset: u16, signature_instruction_index: u16,
+//              public_key_offset: u16, public_key_instruction_index: u16,
+//              message_data_offset: u16, message_data_size: u16,
+//              message_instruction_index: u16
+//
+// Every `*_instruction_index` selects which instruction of the transaction the
+// bytes are read from; `u16::MAX` means the Ed25519 instruction itself.
+// The runtime has already verified every signature by the time our program
+// runs, so all we need is to resolve the offsets exactly the way it does.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::sysvar::instructions::load_instruction_at_checked;
+
+use crate::ErrorCode;
+
+pub const ED25519_PROGRAM_ID: Pubkey =
+    anchor_lang::pubkey!("Ed25519SigVerify111111111111111111111111111");
+
+/// `*_instruction_index` value pointing at the Ed25519 instruction's own data
+pub const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+const OFFSETS_START: usize = 2;
+const OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SignatureOffsets {
+    pub signature_offset: u16,
+    pub signature_instruction_index: u16,
+    pub public_key_offset: u16,
+    pub public_key_instruction_index: u16,
+    pub message_data_offset: u16,
+    pub message_data_size: u16,
+    pub message_instruction_index: u16,
+}
+
+/// One signature the runtime verified, with its referenced bytes resolved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ed25519Signature {
+    pub public_key: [u8; 32],
+    pub signature: [u8; 64],
+    pub message: Vec<u8>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// Header or offset records run past the end of the data
+    Truncated,
+    /// `num_signatures` is zero
+    NoSignatures,
+    /// An offset/size pair points outside the referenced instruction's data
+    OffsetOutOfBounds,
+    /// An instruction index refers to an instruction that doesn't exist
+    BadInstructionIndex,
+}
+
+impl From<ParseError> for ErrorCode {
+    fn from(_: ParseError) -> Self {
+        ErrorCode::InvalidEd25519Data
+    }
+}
+
+/// Read the offset records from Ed25519 instruction data
+pub fn parse_offsets(data: &[u8]) -> std::result::Result<Vec<SignatureOffsets>, ParseError> {
+    if data.len() < OFFSETS_START {
+        return Err(ParseError::Truncated);
+    }
+    let count = data[0] as usize;
+    if count == 0 {
+        return Err(ParseError::NoSignatures);
+    }
+    let end = OFFSETS_START + count * OFFSETS_SIZE;
+    if data.len() < end {
+        return Err(ParseError::Truncated);
+    }
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    Ok((0..count)
+        .map(|i| {
+            let at = OFFSETS_START + i * OFFSETS_SIZE;
+            SignatureOffsets {
+                signature_offset: read_u16(at),
+                signature_instruction_index: read_u16(at + 2),
+                public_key_offset: read_u16(at + 4),
+                public_key_instruction_index: read_u16(at + 6),
+                message_data_offset: read_u16(at + 8),
+                message_data_size: read_u16(at + 10),
+                message_instruction_index: read_u16(at + 12),
+            }
+        })
+        .collect())
+}
+
+/// Resolve every signature in an Ed25519 instruction.
+/// `load` returns the data of the transaction instruction at a given index.
+pub fn parse_signatures<F>(
+    data: &[u8],
+    load: F,
+) -> std::result::Result<Vec<Ed25519Signature>, ParseError>
+where
+    F: Fn(u16) -> Option<Vec<u8>>,
+{
+    let fetch = |index: u16, offset: u16, len: usize| -> std::result::Result<Vec<u8>, ParseError> {
+        let owned;
+        let source: &[u8] = if index == CURRENT_INSTRUCTION {
+            data
+        } else {
+            owned = load(index).ok_or(ParseError::BadInstructionIndex)?;
+            &owned
+        };
+        let start = offset as usize;
+        let end = start.checked_add(len).ok_or(ParseError::OffsetOutOfBounds)?;
+        source
+            .get(start..end)
+            .map(|bytes| bytes.to_vec())
+            .ok_or(ParseError::OffsetOutOfBounds)
+    };
+
+    parse_offsets(data)?
+        .into_iter()
+        .map(|o| {
+            let public_key = fetch(o.public_key_instruction_index, o.public_key_offset, PUBKEY_SIZE)?;
+            let signature = fetch(o.signature_instruction_index, o.signature_offset, SIGNATURE_SIZE)?;
+            let message = fetch(
+                o.message_instruction_index,
+                o.message_data_offset,
+                o.message_data_size as usize,
+            )?;
+            Ok(Ed25519Signature {
+                public_key: public_key.try_into().unwrap(),
+                signature: signature.try_into().unwrap(),
+                message,
+            })
+        })
+        .collect()
+}
+
+/// Signatures carried by one transaction instruction. Instructions for other
+/// programs carry none, and neither does an Ed25519 instruction with
+/// `num_signatures == 0`: the runtime accepts those, so they must not fail
+/// an unrelated claim.
+pub fn instruction_signatures<F>(
+    program_id: &Pubkey,
+    data: &[u8],
+    load: F,
+) -> std::result::Result<Vec<Ed25519Signature>, ParseError>
+where
+    F: Fn(u16) -> Option<Vec<u8>>,
+{
+    if program_id != &ED25519_PROGRAM_ID {
+        return Ok(Vec::new());
+    }
+    match parse_signatures(data, load) {
+        Err(ParseError::NoSignatures) => Ok(Vec::new()),
+        result => result,
+    }
+}
+
+/// Collect every Ed25519-verified signature anywhere in the current transaction
+pub fn load_signatures(instructions_sysvar: &AccountInfo) -> Result<Vec<Ed25519Signature>> {
+    let load = |index: u16| {
+        load_instruction_at_checked(index as usize, instructions_sysvar)
+            .ok()
+            .map(|ix| ix.data)
+    };
+
+    let mut found = Vec::new();
+    let mut index = 0usize;
+    while let Ok(ix) = load_instruction_at_checked(index, instructions_sysvar) {
+        let sigs =
+            instruction_signatures(&ix.program_id, &ix.data, load).map_err(ErrorCode::from)?;
+        found.extend(sigs);
+        index += 1;
+    }
+
+    if found.is_empty() {
+        return err!(ErrorCode::NoEd25519Ix);
+    }
+    Ok(found)
+}
+
+/// Fails unless `signatures` contains (pubkey, signature, message).
+/// On a miss, reports how close the best candidate got.
+pub fn require_signature(
+    signatures: &[Ed25519Signature],
+    expected_pubkey: &[u8; 32],
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let (mut pubkey_seen, mut signature_seen) = (false, false);
+    for sig in signatures.iter().filter(|s| &s.public_key == expected_pubkey) {
+        pubkey_seen = true;
+        if &sig.signature != signature {
+            continue;
+        }
+        signature_seen = true;
+        if sig.message == message {
+            return Ok(());
+        }
+    }
+
+    if signature_seen {
+        err!(ErrorCode::WrongMessage)
+    } else if pubkey_seen {
+        err!(ErrorCode::WrongSignature)
+    } else {
+        err!(ErrorCode::WrongPubkey)
+    }
+}
+
+/// Check that the transaction carries an Ed25519 verification of `message`
+/// signed by `expected_pubkey`
+pub fn verify_signature(
+    instructions_sysvar: &AccountInfo,
+    expected_pubkey: &[u8; 32],
+    message: &[u8],
+    signature: &[u8; 64],
+) -> Result<()> {
+    let signatures = load_signatures(instructions_sysvar)?;
+    require_signature(&signatures, expected_pubkey, message, signature)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offsets_record(o: &SignatureOffsets) -> Vec<u8> {
+        [
+            o.signature_offset,
+            o.signature_instruction_index,
+            o.public_key_offset,
+            o.public_key_instruction_index,
+            o.message_data_offset,
+            o.message_data_size,
+            o.message_instruction_index,
+        ]
+        .iter()
+        .flat_map(|v| v.to_le_bytes())
+        .collect()
+    }
+
+    /// Build a self-contained Ed25519 instruction like the web3.js helper does
+    fn build_ix(entries: &[([u8; 32], [u8; 64], Vec<u8>)]) -> Vec<u8> {
+        let header = OFFSETS_START + entries.len() * OFFSETS_SIZE;
+        let mut body = Vec::new();
+        let mut records = Vec::new();
+        for (pubkey, sig, msg) in entries {
+            let pk_at = (header + body.len()) as u16;
+            body.extend_from_slice(pubkey);
+            let sig_at = (header + body.len()) as u16;
+            body.extend_from_slice(sig);
+            let msg_at = (header + body.len()) as u16;
+            body.extend_from_slice(msg);
+            records.push(SignatureOffsets {
+                signature_offset: sig_at,
+                signature_instruction_index: CURRENT_INSTRUCTION,
+                public_key_offset: pk_at,
+                public_key_instruction_index: CURRENT_INSTRUCTION,
+                message_data_offset: msg_at,
+                message_data_size: msg.len() as u16,
+                message_instruction_index: CURRENT_INSTRUCTION,
+            });
+        }
+        let mut data = vec![entries.len() as u8, 0];
+        for r in &records {
+            data.extend(offsets_record(r));
+        }
+        data.extend(body);
+        data
+    }
+
+    fn no_other_ixs(_: u16) -> Option<Vec<u8>> {
+        None
+    }
+
+    #[test]
+    fn parses_single_signature() {
+        let data = build_ix(&[([1; 32], [2; 64], b"voucher".to_vec())]);
+        let sigs = parse_signatures(&data, no_other_ixs).unwrap();
+        assert_eq!(
+            sigs,
+            vec![Ed25519Signature {
+                public_key: [1; 32],
+                signature: [2; 64],
+                message: b"voucher".to_vec(),
+            }]
+        );
+    }
+
+    #[test]
+    fn parses_every_signature_not_just_the_first() {
+        let data = build_ix(&[
+            ([1; 32], [2; 64], b"first".to_vec()),
+            ([3; 32], [4; 64], b"second".to_vec()),
+            ([5; 32], [6; 64], Vec::new()),
+        ]);
+        let sigs = parse_signatures(&data, no_other_ixs).unwrap();
+        assert_eq!(sigs.len(), 3);
+        assert_eq!(sigs[1].public_key, [3; 32]);
+        assert_eq!(sigs[1].message, b"second");
+        assert!(sigs[2].message.is_empty());
+    }
+
+    #[test]
+    fn resolves_bytes_from_other_instructions() {
+        // Message lives in instruction #3 (e.g. the claim itself), not in the precompile ix
+        let other = b"xxxxVOUCHERyyyy".to_vec();
+        let mut data = build_ix(&[([7; 32], [8; 64], b"decoy!!".to_vec())]);
+        let mut rec = parse_offsets(&data).unwrap()[0];
+        rec.message_instruction_index = 3;
+        rec.message_data_offset = 4;
+        rec.message_data_size = 7;
+        data[OFFSETS_START..OFFSETS_START + OFFSETS_SIZE].copy_from_slice(&offsets_record(&rec));
+
+        let load = |i: u16| if i == 3 { Some(other.clone()) } else { None };
+        let sigs = parse_signatures(&data, load).unwrap();
+        assert_eq!(sigs[0].message, b"VOUCHER");
+        assert_eq!(sigs[0].public_key, [7; 32]);
+    }
+
+    #[test]
+    fn rejects_missing_instruction_index() {
+        let mut data = build_ix(&[([1; 32], [2; 64], b"m".to_vec())]);
+        let mut rec = parse_offsets(&data).unwrap()[0];
+        rec.public_key_instruction_index = 9;
+        data[OFFSETS_START..OFFSETS_START + OFFSETS_SIZE].copy_from_slice(&offsets_record(&rec));
+        assert_eq!(
+            parse_signatures(&data, no_other_ixs),
+            Err(ParseError::BadInstructionIndex)
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_and_empty_headers() {
+        assert_eq!(parse_offsets(&[]), Err(ParseError::Truncated));
+        assert_eq!(parse_offsets(&[1]), Err(ParseError::Truncated));
+        assert_eq!(parse_offsets(&[0, 0]), Err(ParseError::NoSignatures));
+        // Claims two signatures but only carries one offset record
+        let mut data = build_ix(&[([1; 32], [2; 64], b"m".to_vec())]);
+        data[0] = 2;
+        data.truncate(OFFSETS_START + OFFSETS_SIZE + 4);
+        assert_eq!(parse_offsets(&data), Err(ParseError::Truncated));
+    }
+
+    #[test]
+    fn skips_empty_and_foreign_instructions() {
+        let empty = instruction_signatures(&ED25519_PROGRAM_ID, &[0, 0], no_other_ixs);
+        assert_eq!(empty, Ok(Vec::new()));
+        let data = build_ix(&[([1; 32], [2; 64], b"m".to_vec())]);
+        let foreign = instruction_signatures(&Pubkey::default(), &data, no_other_ixs);
+        assert_eq!(foreign, Ok(Vec::new()));
+        assert_eq!(
+            instruction_signatures(&ED25519_PROGRAM_ID, &data, no_other_ixs).unwrap().len(),
+            1
+        );
+        // Malformed headers are still rejected
+        assert_eq!(
+            instruction_signatures(&ED25519_PROGRAM_ID, &[1], no_other_ixs),
+            Err(ParseError::Truncated)
+        );
+    }
+
+    #[test]
+    fn rejects_offsets_past_end_of_data() {
+        let base = build_ix(&[([1; 32], [2; 64], b"msg".to_vec())]);
+        let rec = parse_offsets(&base).unwrap()[0];
+
+        let cases = [
+            SignatureOffsets { public_key_offset: base.len() as u16 - 31, ..rec },
+            SignatureOffsets { signature_offset: base.len() as u16 - 63, ..rec },
+            SignatureOffsets { message_data_size: 4, ..rec },
+            SignatureOffsets { message_data_offset: u16::MAX, message_data_size: u16::MAX, ..rec },
+        ];
+        for bad in cases {
+            let mut data = base.clone();
+            data[OFFSETS_START..OFFSETS_START + OFFSETS_SIZE].copy_from_slice(&offsets_record(&bad));
+            assert_eq!(
+                parse_signatures(&data, no_other_ixs),
+                Err(ParseError::OffsetOutOfBounds)
+            );
+        }
+    }
+
+    #[test]
+    fn require_signature_reports_closest_miss() {
+        let sigs = parse_signatures(
+            &build_ix(&[
+                ([1; 32], [2; 64], b"a".to_vec()),
+                ([3; 32], [4; 64], b"b".to_vec()),
+            ]),
+            no_other_ixs,
+        )
+        .unwrap();
+
+        assert!(require_signature(&sigs, &[3; 32], b"b", &[4; 64]).is_ok());
+        assert_eq!(
+            require_signature(&sigs, &[9; 32], b"b", &[4; 64]).unwrap_err(),
+            ErrorCode::WrongPubkey.into()
+        );
+        assert_eq!(
+            require_signature(&sigs, &[3; 32], b"b", &[2; 64]).unwrap_err(),
+            ErrorCode::WrongSignature.into()
+        );
+        assert_eq!(
+            require_signature(&sigs, &[3; 32], b"a", &[4; 64]).unwrap_err(),
+            ErrorCode::WrongMessage.into()
+        );
+    }
+}
@@ -0,0 +1,173 @@
+// ============ GEO: FIXED-POINT COORDINATES + GEOHASH ============
+//
+// Coordinates travel as integer microdegrees (1e-6 degree, ~11 cm) so the
+// backend never has to reproduce IEEE-754 bytes and the program never
+// formats floats. Geohash cells are computed from the integers with exact
+// integer arithmetic, so on-chain and off-chain encoders always agree.
+
+use crate::ErrorCode;
+use solana_sha256_hasher::hashv;
+
+pub const MICRODEGREES_PER_DEGREE: i64 = 1_000_000;
+pub const MAX_LAT_MICRO: i32 = 90_000_000;
+pub const MAX_LON_MICRO: i32 = 180_000_000;
+
+/// Geohash characters carried in vouchers (8 chars = ~38m x 19m cell)
+pub const GEOHASH_LEN: usize = 8;
+/// Geohash characters made public for private claims (5 chars = ~4.9km cell)
+pub const COARSE_GEOHASH_LEN: usize = 5;
+
+const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GeoError {
+    LatitudeOutOfRange,
+    LongitudeOutOfRange,
+    BadPrecision,
+}
+
+impl From<GeoError> for ErrorCode {
+    fn from(_: GeoError) -> Self {
+        ErrorCode::InvalidCoordinates
+    }
+}
+
+/// Bounds-check a microdegree coordinate pair
+pub fn validate(lat_micro: i32, lon_micro: i32) -> Result<(), GeoError> {
+    if !(-MAX_LAT_MICRO..=MAX_LAT_MICRO).contains(&lat_micro) {
+        return Err(GeoError::LatitudeOutOfRange);
+    }
+    if !(-MAX_LON_MICRO..=MAX_LON_MICRO).contains(&lon_micro) {
+        return Err(GeoError::LongitudeOutOfRange);
+    }
+    Ok(())
+}
+
+/// Degrees -> microdegrees, rounding half away from zero (for clients and V1 vouchers)
+pub fn quantize(degrees: f64) -> i32 {
+    (degrees * MICRODEGREES_PER_DEGREE as f64).round() as i32
+}
+
+/// Microdegrees -> "36.1699" (4 decimals, rounded half away from zero)
+pub fn format_microdegrees(micro: i32) -> String {
+    let sign = if micro < 0 { "-" } else { "" };
+    let ten_thousandths = (micro.unsigned_abs() as u64 + 50) / 100;
+    format!("{}{}.{:04}", sign, ten_thousandths / 10_000, ten_thousandths % 10_000)
+}
+
+/// Cell index along one axis: floor((v - min) / span * 2^bits), top edge folded in
+fn axis_index(value_micro: i32, max_micro: i32, bits: u32) -> u64 {
+    let offset = (value_micro as i64 + max_micro as i64) as u128;
+    let span = 2 * max_micro as u128;
+    let cells = 1u128 << bits;
+    ((offset * cells) / span).min(cells - 1) as u64
+}
+
+/// Geohash of `precision` characters (1..=12)
+pub fn geohash(lat_micro: i32, lon_micro: i32, precision: usize) -> Result<Vec<u8>, GeoError> {
+    validate(lat_micro, lon_micro)?;
+    if precision == 0 || precision > 12 {
+        return Err(GeoError::BadPrecision);
+    }
+
+    // Bits interleave starting with longitude
+    let total_bits = (precision * 5) as u32;
+    let lon_bits = total_bits.div_ceil(2);
+    let lat_bits = total_bits / 2;
+    let lon = axis_index(lon_micro, MAX_LON_MICRO, lon_bits);
+    let lat = axis_index(lat_micro, MAX_LAT_MICRO, lat_bits);
+
+    let mut bits = 0u64;
+    for i in 0..total_bits {
+        let bit = if i % 2 == 0 {
+            (lon >> (lon_bits - 1 - i / 2)) & 1
+        } else {
+            (lat >> (lat_bits - 1 - i / 2)) & 1
+        };
+        bits = (bits << 1) | bit;
+    }
+
+    Ok((0..precision)
+        .map(|c| {
+            let shift = total_bits - 5 * (c as u32 + 1);
+            BASE32[((bits >> shift) & 0x1f) as usize]
+        })
+        .collect())
+}
+
+/// Fixed-width geohash cell used in vouchers
+pub fn geohash_cell(lat_micro: i32, lon_micro: i32) -> Result<[u8; GEOHASH_LEN], GeoError> {
+    let hash = geohash(lat_micro, lon_micro, GEOHASH_LEN)?;
+    let mut cell = [0u8; GEOHASH_LEN];
+    cell.copy_from_slice(&hash);
+    Ok(cell)
+}
+
+/// Cut a cell down to its public prefix for private claims, zero padded
+pub fn coarsen(cell: &[u8; GEOHASH_LEN]) -> [u8; GEOHASH_LEN] {
+    let mut coarse = [0u8; GEOHASH_LEN];
+    coarse[..COARSE_GEOHASH_LEN].copy_from_slice(&cell[..COARSE_GEOHASH_LEN]);
+    coarse
+}
+
+/// True if `cell` is a valid coarse prefix followed only by zero padding
+pub fn is_coarse_cell(cell: &[u8; GEOHASH_LEN]) -> bool {
+    let (prefix, padding) = cell.split_at(COARSE_GEOHASH_LEN);
+    prefix.iter().all(|c| BASE32.contains(c)) && padding.iter().all(|&c| c == 0)
+}
+
+/// Hash a private claim commits to; revealing (coordinates, salt) proves the spot
+pub fn location_commitment(loot_id: u64, lat_micro: i32, lon_micro: i32, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[
+        b"fizzloot-location".as_ref(),
+        &loot_id.to_le_bytes(),
+        &lat_micro.to_le_bytes(),
+        &lon_micro.to_le_bytes(),
+        salt,
+    ])
+    .to_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantizes_and_formats() {
+        assert_eq!(quantize(36.1699), 36_169_900);
+        assert_eq!(quantize(-115.1398), -115_139_800);
+        assert_eq!(quantize(-0.0000005), -1);
+        assert_eq!(format_microdegrees(36_169_900), "36.1699");
+        assert_eq!(format_microdegrees(-115_139_800), "-115.1398");
+        assert_eq!(format_microdegrees(12_345_650), "12.3457");
+        assert_eq!(format_microdegrees(-40), "-0.0000");
+        assert_eq!(format_microdegrees(0), "0.0000");
+    }
+
+    #[test]
+    fn matches_reference_geohashes() {
+        // Wikipedia's Jutland example
+        assert_eq!(geohash(57_649_110, 10_407_440, 11).unwrap(), b"u4pruydqqvj");
+        assert_eq!(&geohash_cell(57_649_110, 10_407_440).unwrap(), b"u4pruydq");
+        assert_eq!(geohash(0, 0, 1).unwrap(), b"s");
+        assert_eq!(geohash(-MAX_LAT_MICRO, -MAX_LON_MICRO, 4).unwrap(), b"0000");
+        assert_eq!(geohash(MAX_LAT_MICRO, MAX_LON_MICRO, 4).unwrap(), b"zzzz");
+    }
+
+    #[test]
+    fn coarsens_cells() {
+        let cell = geohash_cell(57_649_110, 10_407_440).unwrap();
+        let coarse = coarsen(&cell);
+        assert_eq!(&coarse, b"u4pru\0\0\0");
+        assert!(is_coarse_cell(&coarse));
+        assert!(!is_coarse_cell(&cell));
+        assert!(!is_coarse_cell(b"u4pra\0\0\0"));
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        assert_eq!(validate(MAX_LAT_MICRO + 1, 0), Err(GeoError::LatitudeOutOfRange));
+        assert_eq!(validate(0, -MAX_LON_MICRO - 1), Err(GeoError::LongitudeOutOfRange));
+        assert_eq!(geohash(0, 0, 13), Err(GeoError::BadPrecision));
+    }
+}
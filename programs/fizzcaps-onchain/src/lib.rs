@@ -2,14 +2,25 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, burn, Burn, Transfer},
+    token::{self, Mint, MintTo, Token, TokenAccount, burn, Burn, CloseAccount, Transfer},
+};
+use mpl_core::{
+    instructions::{CreateV2CpiBuilder, UpdatePluginV1CpiBuilder},
+    types::{Attribute, Attributes, DataState, Plugin, PluginAuthority, PluginAuthorityPair},
 };
 use mpl_token_metadata::{
-    instructions::CreateV1CpiBuilder,
-    types::{Collection, TokenStandard},
+    instructions::{CreateV1CpiBuilder, VerifyCollectionV1CpiBuilder},
+    types::{Collection, CollectionDetails, TokenStandard},
     ID as METADATA_PROGRAM_ID,
 };
 
+pub mod curve_math;
+pub mod ed25519;
+pub mod geo;
+pub mod merkle;
+
+use curve_math::{BuyQuote, CurveReserves, SellQuote};
+
 declare_id!("DXxzKfZh6aJCff7sEusMU1E9w4ZDwgJkYGgKStRRGRyP");
 
 // ============ SEEDS ============
@@ -19,18 +30,92 @@ const FIZZ_CONFIG_SEEDS: &[u8] = b"fizz-config";
 const FIZZ_CURVE_SEEDS: &[u8] = b"fizz-curve";
 const FIZZ_SOL_VAULT_SEEDS: &[u8] = b"fizz-sol-vault";
 const FIZZ_ADMIN_SEEDS: &[u8] = b"fizz-admin";
+const FIZZ_POOL_SEEDS: &[u8] = b"fizz-pool";
+const FIZZ_LP_MINT_SEEDS: &[u8] = b"fizz-lp-mint";
+const FIZZ_BUYBACK_SEEDS: &[u8] = b"fizz-buyback";
+const SIGNER_REGISTRY_SEEDS: &[u8] = b"signer-registry";
+const LOOT_PLAYER_SEEDS: &[u8] = b"loot-player";
+const LOOT_COLLECTION_SEEDS: &[u8] = b"loot-collection";
+const LOOT_COLLECTION_MINT_SEEDS: &[u8] = b"loot-collection-mint";
+const LOOT_CONFIG_SEEDS: &[u8] = b"loot-config";
+const LOOT_TREE_SEEDS: &[u8] = b"loot-tree";
+const LOOT_ITEM_SEEDS: &[u8] = b"loot-item";
+const LOCATION_CELL_SEEDS: &[u8] = b"location-cell";
+const CELL_COOLDOWN_SEEDS: &[u8] = b"cell-cooldown";
+const SALVAGE_TABLE_SEEDS: &[u8] = b"salvage-table";
+const SALVAGE_POOL_SEEDS: &[u8] = b"salvage-pool";
+const MATERIAL_MINT_SEEDS: &[u8] = b"material-mint";
+const LOOT_METADATA_CONFIG_SEEDS: &[u8] = b"loot-metadata-config";
+const LOOT_ASSET_SEEDS: &[u8] = b"loot-asset";
+const BEACON_REGISTRY_SEEDS: &[u8] = b"beacon-registry";
+const LOOT_WITNESS_SEEDS: &[u8] = b"loot-witness";
+const LOOT_ITEM_STATE_SEEDS: &[u8] = b"loot-item-state";
+const FUSION_RECIPES_SEEDS: &[u8] = b"fusion-recipes";
+const PENDING_FUSION_SEEDS: &[u8] = b"pending-fusion";
+
+// ============ LOOT CONSTANTS ============
+/// Max voucher-signing keys tracked at once (active + rotating in/out)
+const MAX_VOUCHER_SIGNERS: usize = 8;
+/// Free-claim allowances reset on UTC day boundaries
+const SECONDS_PER_DAY: i64 = 86_400;
+/// Season of the root FIZZLOOT collection; higher seasons are sub-collections of it
+const ROOT_LOOT_SEASON: u16 = 0;
+/// Max vouchers per claim_loot_batch (tx size / compute bound)
+const MAX_BATCH_CLAIMS: usize = 5;
+/// remaining_accounts per batch voucher: loot_mint, player_loot_ata, loot_metadata, loot_item,
+/// location_cell, cell_cooldown
+const BATCH_ACCOUNTS_PER_VOUCHER: usize = 6;
+/// LootItem account size
+const LOOT_ITEM_SPACE: usize = 8 + 8 + 32 + LootRoll::SIZE + 32 + 8 + (1 + 32) + 1 + 1;
+/// LocationCell account size
+const LOCATION_CELL_SPACE: usize = 8 + geo::GEOHASH_LEN + 8 + 4 + 1;
+/// CellCooldown account size
+const CELL_COOLDOWN_SPACE: usize = 8 + 8 + 1;
+/// LootPlayer account size
+const LOOT_PLAYER_SPACE: usize = 8 + 32 + NonceWindow::SPACE + 8 + 1 + 1;
+/// Nonces a player may redeem out of order behind their newest one
+const NONCE_WINDOW: u64 = 256;
+/// Max registered location beacons
+const MAX_BEACONS: usize = 16;
+/// Max modifications installed on one loot item
+const MAX_LOOT_MODS: usize = 8;
+const LOOT_ITEM_STATE_SPACE: usize = 8 + 32 + 8 + 2 + 2 + 1 + (4 + MAX_LOOT_MODS * 2) + 8 + 1 + 1;
+/// Most NFTs one `fuse_loot` can burn (3 remaining accounts each)
+const MAX_FUSION_INPUTS: u8 = 10;
+/// PendingFusion account size
+const PENDING_FUSION_SPACE: usize = 8
+    + 32
+    + 8
+    + (4 + MAX_FUSION_INPUTS as usize * 8)
+    + 1
+    + 1
+    + (4 + MAX_FUSION_INPUTS as usize * LootRoll::SIZE)
+    + 8
+    + 1;
+/// High bit marks loot ids minted by fusion, so they never collide with server ids
+const FUSED_LOOT_ID_FLAG: u64 = 1 << 63;
+/// Max length of `LootMetadataConfig.name_template`; placeholders can expand
+/// past `MAX_LOOT_NAME_LEN`, so rendered names are cut to fit
+const MAX_NAME_TEMPLATE_LEN: usize = 64;
+/// Metaplex name limit; a longer name aborts the CreateV1 / CreateV2 CPI
+const MAX_LOOT_NAME_LEN: usize = 32;
+/// Metaplex URI limit
+const MAX_LOOT_URI_LEN: usize = 200;
 
 // ============ FIZZ.FUN CONSTANTS ============
+// Everything below TOTAL_SUPPLY is a default for `FizzLaunchParams` or the
+// launch tier table; the live values sit in `FizzConfig` and are set with
+// `fizz_update_config` / `fizz_set_launch_tiers`.
 /// Total supply per token: 1 billion with 9 decimals
 const TOTAL_SUPPLY: u64 = 1_000_000_000_000_000_000;
-/// Tokens available in bonding curve: 800 million
-const CURVE_SUPPLY: u64 = 800_000_000_000_000_000;
 /// SOL needed to graduate: 85 SOL
 const GRADUATION_SOL: u64 = 85_000_000_000;
 /// Virtual SOL for initial price: 30 SOL
 const VIRTUAL_SOL: u64 = 30_000_000_000;
 /// Trading fee: 1% (100 basis points)
 const FEE_BPS: u64 = 100;
+/// Creator bonus at graduation: 7% of SOL raised
+const CREATOR_BONUS_BPS: u64 = 700;
 /// CAPS decimals
 const CAPS_DECIMALS: u64 = 1_000_000_000;
 /// CAPS required to launch
@@ -41,6 +126,20 @@ const CAPS_LAUNCH_FEE: u64 = 100 * CAPS_DECIMALS;
 const CAPS_VETERAN_THRESHOLD: u64 = 10_000 * CAPS_DECIMALS;
 /// Veteran launch fee (burned)
 const CAPS_VETERAN_FEE: u64 = 50 * CAPS_DECIMALS;
+/// Graduated pool swap fee: 0.3%
+const POOL_FEE_BPS: u64 = 30;
+/// Share of the pool fee sent to the treasury: 20%
+const POOL_TREASURY_SHARE_BPS: u64 = 2_000;
+/// Share of the pool fee set aside for CAPS buybacks: 20% (LPs keep the rest)
+const POOL_BUYBACK_SHARE_BPS: u64 = 2_000;
+/// Highest trading fee `fizz_update_config` accepts: 10%
+const MAX_FEE_BPS: u64 = 1_000;
+/// Highest creator bonus a launch tier may grant: 20%
+const MAX_CREATOR_BONUS_BPS: u64 = 2_000;
+/// Max rows in `FizzConfig.launch_tiers`
+const MAX_LAUNCH_TIERS: usize = 4;
+/// Smallest share of TOTAL_SUPPLY left out of the curve to seed the graduated pool: 10%
+const MIN_POOL_SUPPLY_BPS: u64 = 1_000;
 
 #[program]
 pub mod fizzcaps_onchain {
@@ -48,591 +147,4885 @@ pub mod fizzcaps_onchain {
 
     // ============ EXISTING: LOOT CLAIM ============
     
-    pub fn claim_loot(ctx: Context<ClaimLoot>, voucher: LootVoucher) -> Result<()> {
-        let fee_amount = 100 * 10u64.pow(9);
-
-        // 1. Burn the $CAPS fee
-        burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.caps_mint.to_account_info(),
-                    from: ctx.accounts.player_caps_ata.to_account_info(),
-                    authority: ctx.accounts.player.to_account_info(),
-                },
-            ),
-            fee_amount,
-        )?;
-
-        // 2. Verify server Ed25519 signature
-        verify_ed25519_signature(
-            &ctx.accounts.instructions_sysvar,
-            &ctx.accounts.server_key.key().to_bytes(),
-            &voucher.try_to_vec()?,
-            &voucher.server_signature,
+    /// remaining_accounts: the `LootWitness` account of each registered player
+    /// among `attestations` (beacons need none)
+    pub fn claim_loot(
+        ctx: Context<ClaimLoot>,
+        voucher: LootVoucher,
+        payment: LootPaymentMethod,
+        attestations: Vec<WitnessAttestation>,
+    ) -> Result<()> {
+        // 1-3. Voucher, geocell and signature checks, then the fee
+        let prepared = prepare_claim(
+            &mut ctx.accounts.claim,
+            &ctx.bumps.claim,
+            &voucher,
+            LootClaimMode::Nft,
+            payment,
+            &attestations,
+            ctx.remaining_accounts,
         )?;
 
-        // 3. Mint 1 Loot NFT to player
-        anchor_spl::token::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.loot_mint.to_account_info(),
-                    to: ctx.accounts.player_loot_ata.to_account_info(),
-                    authority: ctx.accounts.loot_mint_authority.to_account_info(),
-                },
-                &[&[LOOT_MINT_AUTHORITY_SEEDS, &[ctx.bumps.loot_mint_authority]]],
-            ),
-            1,
+        // 4. Create the loot mint + ATA and mint 1 Loot NFT to player, with metadata
+        let claim = &ctx.accounts.claim;
+        let mint_accounts = LootMintAccounts {
+            payer: claim.fee_payer.to_account_info(),
+            player: claim.player.to_account_info(),
+            loot_mint: ctx.accounts.loot_mint.to_account_info(),
+            player_loot_ata: ctx.accounts.player_loot_ata.to_account_info(),
+            loot_metadata: ctx.accounts.loot_metadata.to_account_info(),
+            loot_mint_authority: ctx.accounts.loot_mint_authority.to_account_info(),
+            loot_mint_authority_bump: ctx.bumps.loot_mint_authority,
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+            token_program: claim.token_program.to_account_info(),
+            system_program: claim.system_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            instructions_sysvar: claim.instructions_sysvar.to_account_info(),
+            metadata_program: ctx.accounts.metadata_program.to_account_info(),
+        };
+        init_loot_mint_and_ata(&mint_accounts, voucher.payload.loot_id())?;
+        mint_loot_nft(
+            &mint_accounts,
+            &claim.loot_metadata_config,
+            claim.loot_metadata_config.render_name(&voucher.payload),
+            claim.loot_metadata_config.render_uri(&voucher.payload),
         )?;
 
-        let name = format!(
-            "Fizz Cache #{} @ ({:.4},{:.4}) {}",
-            voucher.loot_id, voucher.latitude, voucher.longitude, voucher.location_hint
+        // 5. Record the rolled item, reserving the loot id across claim paths
+        let mint = ctx.accounts.loot_mint.key();
+        let event = record_claim(claim, &voucher, &prepared, payment, mint)?;
+        emit_cpi!(event);
+        msg!(
+            "Loot #{} (v{}) claimed by {} at {}!",
+            voucher.payload.loot_id(),
+            voucher.payload.version(),
+            claim.player.key(),
+            voucher.payload.location_label()
         );
 
-        // 4. Create Metadata for the NFT
-        CreateV1CpiBuilder::new(&ctx.accounts.metadata_program)
-            .metadata(&ctx.accounts.loot_metadata)
-            .mint(&ctx.accounts.loot_mint.to_account_info(), true)
-            .authority(&ctx.accounts.loot_mint_authority)
-            .payer(&ctx.accounts.player)
-            .update_authority(&ctx.accounts.loot_mint_authority, true)
-            .system_program(&ctx.accounts.system_program)
-            .sysvar_instructions(&ctx.accounts.instructions_sysvar)
-            .token_standard(TokenStandard::NonFungible)
-            .name(name)
-            .symbol("FIZZLOOT".to_string())
-            .uri(format!("https://atomicfizzcaps.xyz/loot/{}.json", voucher.loot_id))
-            .seller_fee_basis_points(0)
-            .creators(vec![])
-            .collection(Collection {
-                verified: false,
-                key: Pubkey::default(),
-            })
-            .is_mutable(false)
-            .primary_sale_happened(true)
-            .invoke_signed(&[&[LOOT_MINT_AUTHORITY_SEEDS, &[ctx.bumps.loot_mint_authority]]])?;
+        Ok(())
+    }
 
-        msg!(
-            "Loot #{} claimed by {} at ({:.4}, {:.4})!",
-            voucher.loot_id,
-            ctx.accounts.player.key(),
-            voucher.latitude,
-            voucher.longitude
+    /// Claim several caches in one transaction.
+    /// One Ed25519 instruction carries every voucher signature; the fee is charged once.
+    /// Caches that need witness attestations must be claimed individually.
+    /// remaining_accounts: [loot_mint, player_loot_ata, loot_metadata, loot_item, location_cell,
+    /// cell_cooldown] per voucher, in order.
+    pub fn claim_loot_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ClaimLootBatch<'info>>,
+        vouchers: Vec<LootVoucher>,
+        payment: LootPaymentMethod,
+    ) -> Result<()> {
+        require!(!vouchers.is_empty(), ErrorCode::EmptyBatch);
+        require!(vouchers.len() <= MAX_BATCH_CLAIMS, ErrorCode::BatchTooLarge);
+        require!(
+            ctx.remaining_accounts.len() == vouchers.len() * BATCH_ACCOUNTS_PER_VOUCHER,
+            ErrorCode::BatchAccountsMismatch
         );
 
+        let now = Clock::get()?.unix_timestamp;
+        if ctx.accounts.player_state.player == Pubkey::default() {
+            ctx.accounts.player_state.player = ctx.accounts.player.key();
+            ctx.accounts.player_state.bump = ctx.bumps.player_state;
+        }
+
+        // 1. Charge the combined fee once (the tx reverts if any voucher fails)
+        let mut fees_burned = Vec::with_capacity(vouchers.len());
+        let (mut fee_burned, mut fee_to_treasury) = (0u64, 0u64);
+        for voucher in &vouchers {
+            let fee = loot_fee_due(
+                &ctx.accounts.loot_config,
+                &mut ctx.accounts.player_state,
+                voucher.payload.roll().rarity,
+                now,
+            )?;
+            let (burned, to_treasury) = ctx.accounts.loot_config.split_fee(fee);
+            fee_burned = fee_burned.checked_add(burned).ok_or(ErrorCode::FeeOverflow)?;
+            fee_to_treasury = fee_to_treasury.checked_add(to_treasury).ok_or(ErrorCode::FeeOverflow)?;
+            fees_burned.push(burned);
+        }
+        fee_to_treasury = fee_to_treasury
+            .checked_add(ctx.accounts.loot_config.rent_reimbursement(
+                &ctx.accounts.fee_payer.key(),
+                &ctx.accounts.player.key(),
+                vouchers.len() as u64,
+            )?)
+            .ok_or(ErrorCode::FeeOverflow)?;
+        charge_claim_fee(
+            &ClaimFeeAccounts {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                caps_mint: ctx.accounts.caps_mint.to_account_info(),
+                player_caps_ata: ctx.accounts.player_caps_ata.to_account_info(),
+                treasury_caps_ata: ctx.accounts.treasury_caps_ata.to_account_info(),
+                player: ctx.accounts.player.to_account_info(),
+                sol_treasury: ctx.accounts.sol_treasury.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            &ctx.accounts.loot_config,
+            payment,
+            fee_burned,
+            fee_to_treasury,
+        )?;
+        if payment == LootPaymentMethod::Sol {
+            fees_burned.fill(0);
+        }
+
+        let player = ctx.accounts.player.key();
+        let server_key = ctx.accounts.server_key.key();
+        let signatures = ed25519::load_signatures(&ctx.accounts.instructions_sysvar)?;
+
+        for (i, (voucher, accounts)) in vouchers
+            .iter()
+            .zip(ctx.remaining_accounts.chunks(BATCH_ACCOUNTS_PER_VOUCHER))
+            .enumerate()
+        {
+            let loot_id = voucher.payload.loot_id();
+            let mut claim = || -> Result<()> {
+                // 2. Same checks as claim_loot, per voucher
+                require!(
+                    ctx.accounts.loot_config.resolve_claim_mode(&voucher.payload)?
+                        == LootClaimMode::Nft,
+                    ErrorCode::WrongClaimMode
+                );
+                require!(
+                    !ctx.accounts.loot_config.needs_witnesses(voucher.payload.roll().rarity),
+                    ErrorCode::NotEnoughWitnesses
+                );
+                validate_voucher(
+                    voucher,
+                    &ctx.accounts.signer_registry,
+                    &mut ctx.accounts.player_state,
+                    &player,
+                    &server_key,
+                    now,
+                )?;
+                record_cell_claim_by_hand(
+                    ctx.accounts,
+                    &accounts[4],
+                    &accounts[5],
+                    ctx.accounts.loot_config.cell_key(&voucher.payload),
+                    now,
+                )?;
+                ed25519::require_signature(
+                    &signatures,
+                    &server_key.to_bytes(),
+                    &voucher.message()?,
+                    &voucher.server_signature,
+                )?;
+
+                // 3. Create the loot mint + ATA and reserve the loot id
+                let mint_accounts = LootMintAccounts {
+                    payer: ctx.accounts.fee_payer.to_account_info(),
+                    player: ctx.accounts.player.to_account_info(),
+                    loot_mint: accounts[0].clone(),
+                    player_loot_ata: accounts[1].clone(),
+                    loot_metadata: accounts[2].clone(),
+                    loot_mint_authority: ctx.accounts.loot_mint_authority.to_account_info(),
+                    loot_mint_authority_bump: ctx.bumps.loot_mint_authority,
+                    collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                    collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                    collection_master_edition: ctx
+                        .accounts
+                        .collection_master_edition
+                        .to_account_info(),
+                    token_program: ctx.accounts.token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                    associated_token_program: ctx
+                        .accounts
+                        .associated_token_program
+                        .to_account_info(),
+                    instructions_sysvar: ctx.accounts.instructions_sysvar.to_account_info(),
+                    metadata_program: ctx.accounts.metadata_program.to_account_info(),
+                };
+                init_loot_mint_and_ata(&mint_accounts, loot_id)?;
+                reserve_loot_item(
+                    &mint_accounts.payer,
+                    &mint_accounts.system_program,
+                    &accounts[3],
+                    LootItem {
+                        loot_id,
+                        mint: accounts[0].key(),
+                        roll: voucher.payload.roll(),
+                        claimed_by: player,
+                        claimed_at: now,
+                        location_commitment: voucher.payload.location_commitment(),
+                        burned: false,
+                        bump: 0,
+                    },
+                )?;
+
+                // 4. Mint + metadata
+                mint_loot_nft(
+                    &mint_accounts,
+                    &ctx.accounts.loot_metadata_config,
+                    ctx.accounts.loot_metadata_config.render_name(&voucher.payload),
+                    ctx.accounts.loot_metadata_config.render_uri(&voucher.payload),
+                )
+            };
+            claim().map_err(|e| {
+                msg!("Batch voucher #{} (loot #{}) rejected: {}", i, loot_id, e);
+                e
+            })?;
+
+            let coordinates = voucher.payload.coordinates();
+            emit_cpi!(LootClaimed {
+                loot_id,
+                player,
+                mint: accounts[0].key(),
+                payment,
+                fee_burned: fees_burned[i],
+                signer: server_key,
+                lat_micro: coordinates.map(|(lat, _)| lat),
+                lon_micro: coordinates.map(|(_, lon)| lon),
+                location_commitment: voucher.payload.location_commitment(),
+                timestamp: now,
+            });
+
+            msg!(
+                "Loot #{} (v{}) claimed by {} (batch #{})",
+                loot_id,
+                voucher.payload.version(),
+                player,
+                i
+            );
+        }
+
         Ok(())
     }
 
-    // ============ FIZZ.FUN: TOKEN LAUNCHPAD ============
+    /// Claim a cache as a leaf in the program's compressed loot tree.
+    /// No mint, token account or metadata account is created; only the small
+    /// LootItem record that reserves the loot id on every claim path.
+    /// Name/URI match claim_loot and are emitted for indexers.
+    pub fn claim_loot_compressed(
+        ctx: Context<ClaimLootCompressed>,
+        voucher: LootVoucher,
+        payment: LootPaymentMethod,
+        attestations: Vec<WitnessAttestation>,
+    ) -> Result<()> {
+        // 1-3. Same checks and fee as claim_loot
+        let prepared = prepare_claim(
+            &mut ctx.accounts.claim,
+            &ctx.bumps.claim,
+            &voucher,
+            LootClaimMode::Compressed,
+            payment,
+            &attestations,
+            ctx.remaining_accounts,
+        )?;
 
-    /// Initialize Fizz.fun configuration
-    pub fn fizz_init(ctx: Context<FizzInit>) -> Result<()> {
-        let config = &mut ctx.accounts.config;
-        config.authority = ctx.accounts.authority.key();
-        config.treasury = ctx.accounts.treasury.key();
-        config.caps_mint = ctx.accounts.caps_mint.key();
-        config.total_tokens_launched = 0;
-        config.total_volume_sol = 0;
-        config.total_caps_burned = 0;
-        config.admin_usdc_launches = 0;
-        config.bump = ctx.bumps.config;
-        
-        msg!("Fizz.fun initialized! Treasury: {}", config.treasury);
+        // 4. Append the leaf
+        let claim = &ctx.accounts.claim;
+        let loot_id = voucher.payload.loot_id();
+        let owner = claim.player.key();
+        let name = claim.loot_metadata_config.render_name(&voucher.payload);
+        let uri = claim.loot_metadata_config.render_uri(&voucher.payload);
+        let leaf = loot_leaf(loot_id, &owner, &prepared.roll, &name, &uri)?;
+
+        let loot_tree = &mut ctx.accounts.loot_tree;
+        let leaf_index = loot_tree.tree.append(leaf).ok_or(ErrorCode::LootTreeFull)?;
+        emit!(CompressedLootMinted {
+            tree: loot_tree.key(),
+            leaf_index,
+            leaf,
+            root: loot_tree.tree.root(),
+            loot_id,
+            owner,
+            roll: prepared.roll,
+            name,
+            uri,
+            location_commitment: voucher.payload.location_commitment(),
+            payment,
+            fee_burned: prepared.fee_burned,
+        });
+
+        // 5. Record the rolled item, reserving the loot id across claim paths
+        let event = record_claim(claim, &voucher, &prepared, payment, loot_tree.key())?;
+        emit_cpi!(event);
+
+        msg!("Loot #{} claimed by {} as leaf {}", loot_id, owner, leaf_index);
         Ok(())
     }
 
-    /// Add an admin (only authority)
-    pub fn fizz_add_admin(ctx: Context<FizzManageAdmin>, admin: Pubkey) -> Result<()> {
-        let admin_record = &mut ctx.accounts.admin_record;
-        admin_record.admin = admin;
-        admin_record.added_at = Clock::get()?.unix_timestamp;
-        admin_record.is_active = true;
-        admin_record.bump = ctx.bumps.admin_record;
-        
-        msg!("Fizz.fun admin added: {}", admin);
+    /// Claim a cache as a Metaplex Core asset: one account per item instead
+    /// of mint + ATA + metadata, with the roll stored in an Attributes plugin
+    /// that the program (update authority) can rewrite later.
+    pub fn claim_loot_core(
+        ctx: Context<ClaimLootCore>,
+        voucher: LootVoucher,
+        payment: LootPaymentMethod,
+        attestations: Vec<WitnessAttestation>,
+    ) -> Result<()> {
+        // 1-3. Same checks and fee as claim_loot
+        let prepared = prepare_claim(
+            &mut ctx.accounts.claim,
+            &ctx.bumps.claim,
+            &voucher,
+            LootClaimMode::Core,
+            payment,
+            &attestations,
+            ctx.remaining_accounts,
+        )?;
+
+        // 4. Create the asset at its loot id PDA, owned by the player
+        let claim = &ctx.accounts.claim;
+        let loot_id = voucher.payload.loot_id();
+        let loot_id_bytes = loot_id.to_le_bytes();
+        CreateV2CpiBuilder::new(&ctx.accounts.mpl_core_program)
+            .asset(&ctx.accounts.loot_asset)
+            .payer(&claim.fee_payer)
+            .owner(Some(&claim.player))
+            .update_authority(Some(&ctx.accounts.loot_mint_authority))
+            .system_program(&claim.system_program)
+            .data_state(DataState::AccountState)
+            .name(claim.loot_metadata_config.render_name(&voucher.payload))
+            .uri(claim.loot_metadata_config.render_uri(&voucher.payload))
+            .plugins(vec![PluginAuthorityPair {
+                plugin: Plugin::Attributes(core_loot_attributes(&prepared.roll)),
+                authority: Some(PluginAuthority::UpdateAuthority),
+            }])
+            .invoke_signed(&[&[LOOT_ASSET_SEEDS, &loot_id_bytes, &[ctx.bumps.loot_asset]]])?;
+
+        // 5. Record the rolled item, reserving the loot id across claim paths
+        let mint = ctx.accounts.loot_asset.key();
+        let event = record_claim(claim, &voucher, &prepared, payment, mint)?;
+        emit_cpi!(event);
+        msg!(
+            "Loot #{} claimed by {} as Core asset {}",
+            loot_id,
+            claim.player.key(),
+            ctx.accounts.loot_asset.key()
+        );
+
         Ok(())
     }
 
-    /// Launch a new token (requires 1000+ CAPS)
-    /// Name/symbol/uri emitted in event for off-chain indexing (saves rent!)
-    pub fn fizz_create_token(
-        ctx: Context<FizzCreateToken>,
-        name: String,
-        symbol: String,
-        uri: String,
+    /// Prove where a private claim happened by revealing the committed
+    /// coordinates and salt (only the claiming player)
+    pub fn reveal_loot_location(
+        ctx: Context<RevealLootLocation>,
+        lat_micro: i32,
+        lon_micro: i32,
+        salt: [u8; 32],
     ) -> Result<()> {
-        // Validate inputs
-        require!(name.len() <= 32, FizzError::NameTooLong);
-        require!(symbol.len() <= 10, FizzError::SymbolTooLong);
-        require!(uri.len() <= 200, FizzError::UriTooLong);
+        let item = &ctx.accounts.loot_item;
+        let commitment = item.location_commitment.ok_or(ErrorCode::LocationNotPrivate)?;
+        require!(
+            geo::location_commitment(item.loot_id, lat_micro, lon_micro, &salt) == commitment,
+            ErrorCode::LocationRevealMismatch
+        );
+        let geohash = geo::geohash_cell(lat_micro, lon_micro).map_err(ErrorCode::from)?;
+
+        emit!(LootLocationRevealed {
+            loot_id: item.loot_id,
+            player: ctx.accounts.player.key(),
+            lat_micro,
+            lon_micro,
+            geohash,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        // 1. Verify CAPS balance >= 1000
-        let caps_balance = ctx.accounts.creator_caps_ata.amount;
-        require!(caps_balance >= CAPS_TO_LAUNCH, FizzError::InsufficientCapsToLaunch);
+        msg!("Loot #{} location revealed", item.loot_id);
+        Ok(())
+    }
 
-        // 2. Calculate launch fee (veterans get discount)
-        let launch_fee = if caps_balance >= CAPS_VETERAN_THRESHOLD {
-            CAPS_VETERAN_FEE
-        } else {
-            CAPS_LAUNCH_FEE
-        };
+    // ============ LOOT CONFIG ============
 
-        // 3. Burn CAPS launch fee
-        token::burn(
-            CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Burn {
-                    mint: ctx.accounts.caps_mint.to_account_info(),
-                    from: ctx.accounts.creator_caps_ata.to_account_info(),
-                    authority: ctx.accounts.creator.to_account_info(),
-                },
-            ),
-            launch_fee,
-        )?;
+    /// Create the loot config (only authority)
+    pub fn init_loot_config(ctx: Context<InitLootConfig>, params: LootConfigParams) -> Result<()> {
+        let config = &mut ctx.accounts.loot_config;
+        config.bump = ctx.bumps.loot_config;
+        config.apply(&params)?;
 
-        // 4. Initialize bonding curve (minimal on-chain data for cheap rent)
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.creator = ctx.accounts.creator.key();
-        curve.token_mint = ctx.accounts.token_mint.key();
-        curve.sol_reserve = 0;
-        curve.token_reserve = CURVE_SUPPLY;
-        curve.graduated = false;
-        curve.created_at = Clock::get()?.unix_timestamp;
-        curve.launch_type = FizzLaunchType::CapsStandard;
-        curve.bump = ctx.bumps.bonding_curve;
+        emit!(LootConfigUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        // 5. Mint total supply to curve vault
-        token::mint_to(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                MintTo {
-                    mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.curve_token_vault.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                },
-                &[&[
-                    FIZZ_CURVE_SEEDS,
-                    ctx.accounts.token_mint.key().as_ref(),
-                    &[curve.bump],
-                ]],
-            ),
-            TOTAL_SUPPLY,
-        )?;
+    /// Retune claim fee, burn/treasury split, free claims and claim mode
+    /// without a redeploy (only authority)
+    pub fn update_loot_config(ctx: Context<ManageLootConfig>, params: LootConfigParams) -> Result<()> {
+        ctx.accounts.loot_config.apply(&params)?;
 
-        // 6. Update config
-        let config = &mut ctx.accounts.config;
-        config.total_tokens_launched += 1;
-        config.total_caps_burned += launch_fee;
+        emit!(LootConfigUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
 
-        emit!(FizzTokenCreated {
-            mint: ctx.accounts.token_mint.key(),
-            creator: ctx.accounts.creator.key(),
-            name,
-            symbol,
-            launch_type: FizzLaunchType::CapsStandard,
-            caps_burned: launch_fee,
+    /// Create the loot metadata config (only authority)
+    pub fn init_loot_metadata_config(
+        ctx: Context<InitLootMetadataConfig>,
+        params: LootMetadataParams,
+    ) -> Result<()> {
+        let metadata_config = &mut ctx.accounts.loot_metadata_config;
+        metadata_config.bump = ctx.bumps.loot_metadata_config;
+        metadata_config.apply(&params)?;
+
+        emit!(LootMetadataConfigUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
+    }
+
+    /// Point loot metadata at another deployment's name/symbol/URI templates (only authority)
+    pub fn update_loot_metadata_config(
+        ctx: Context<ManageLootMetadataConfig>,
+        params: LootMetadataParams,
+    ) -> Result<()> {
+        ctx.accounts.loot_metadata_config.apply(&params)?;
+
+        emit!(LootMetadataConfigUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
             timestamp: Clock::get()?.unix_timestamp,
         });
+        Ok(())
+    }
 
+    /// Create a compressed loot tree (only authority)
+    pub fn init_loot_tree(ctx: Context<InitLootTree>, tree_id: u32, depth: u8) -> Result<()> {
+        let loot_tree = &mut ctx.accounts.loot_tree;
+        loot_tree.tree_id = tree_id;
+        loot_tree.tree = merkle::MerkleTree::new(depth).ok_or(ErrorCode::InvalidTreeDepth)?;
+        loot_tree.bump = ctx.bumps.loot_tree;
+
+        msg!("Loot tree {} created (depth {})", tree_id, depth);
         Ok(())
     }
 
-    /// Admin launch with USDC (pre-mainnet bootstrap)
-    pub fn fizz_create_token_admin(
-        ctx: Context<FizzCreateTokenAdmin>,
+    // ============ LOOT COLLECTIONS ============
+
+    /// Create the FIZZLOOT collection NFT (season 0) or a seasonal
+    /// sub-collection of it (only authority). The collection is owned and
+    /// updated by the loot_mint_authority PDA.
+    pub fn create_loot_collection(
+        ctx: Context<CreateLootCollection>,
+        season: u16,
         name: String,
-        symbol: String,
         uri: String,
     ) -> Result<()> {
         require!(name.len() <= 32, FizzError::NameTooLong);
-        require!(symbol.len() <= 10, FizzError::SymbolTooLong);
         require!(uri.len() <= 200, FizzError::UriTooLong);
-        require!(ctx.accounts.admin_record.is_active, FizzError::AdminInactive);
 
-        // Initialize bonding curve (minimal on-chain data, no CAPS burn for admin)
-        let curve = &mut ctx.accounts.bonding_curve;
-        curve.creator = ctx.accounts.creator.key();
-        curve.token_mint = ctx.accounts.token_mint.key();
-        curve.sol_reserve = 0;
-        curve.token_reserve = CURVE_SUPPLY;
-        curve.graduated = false;
-        curve.created_at = Clock::get()?.unix_timestamp;
-        curve.launch_type = FizzLaunchType::AdminUSDC; // Clearly marked!
-        curve.bump = ctx.bumps.bonding_curve;
+        let signer_seeds: &[&[&[u8]]] =
+            &[&[LOOT_MINT_AUTHORITY_SEEDS, &[ctx.bumps.loot_mint_authority]]];
 
-        // Mint total supply to curve vault
+        // Sub-collections nest under the root collection
+        let parent = if season == ROOT_LOOT_SEASON {
+            None
+        } else {
+            let root = ctx
+                .accounts
+                .parent_collection
+                .as_ref()
+                .ok_or(ErrorCode::MissingParentCollection)?;
+            require!(root.season == ROOT_LOOT_SEASON, ErrorCode::MissingParentCollection);
+            Some(root.mint)
+        };
+
+        // 1. Mint the single collection token to the authority PDA
         token::mint_to(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 MintTo {
-                    mint: ctx.accounts.token_mint.to_account_info(),
-                    to: ctx.accounts.curve_token_vault.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                    mint: ctx.accounts.collection_mint.to_account_info(),
+                    to: ctx.accounts.collection_token_account.to_account_info(),
+                    authority: ctx.accounts.loot_mint_authority.to_account_info(),
                 },
-                &[&[
-                    FIZZ_CURVE_SEEDS,
-                    ctx.accounts.token_mint.key().as_ref(),
-                    &[curve.bump],
-                ]],
+                signer_seeds,
             ),
-            TOTAL_SUPPLY,
+            1,
         )?;
 
-        // Update config
-        let config = &mut ctx.accounts.config;
-        config.total_tokens_launched += 1;
-        config.admin_usdc_launches += 1;
+        // 2. Metadata + master edition, flagged as a sized collection
+        let collection_mint = ctx.accounts.collection_mint.to_account_info();
+        let mut create = CreateV1CpiBuilder::new(&ctx.accounts.metadata_program);
+        create
+            .metadata(&ctx.accounts.collection_metadata)
+            .master_edition(Some(&ctx.accounts.collection_master_edition))
+            .mint(&collection_mint, false)
+            .authority(&ctx.accounts.loot_mint_authority)
+            .payer(&ctx.accounts.authority)
+            .update_authority(&ctx.accounts.loot_mint_authority, true)
+            .system_program(&ctx.accounts.system_program)
+            .sysvar_instructions(&ctx.accounts.instructions_sysvar)
+            .spl_token_program(Some(&ctx.accounts.token_program))
+            .token_standard(TokenStandard::NonFungible)
+            .name(name)
+            .symbol("FIZZLOOT".to_string())
+            .uri(uri)
+            .seller_fee_basis_points(0)
+            .creators(vec![])
+            .collection_details(CollectionDetails::V1 { size: 0 })
+            .is_mutable(true)
+            .primary_sale_happened(true);
+        if let Some(parent_mint) = parent {
+            create.collection(Collection {
+                verified: false,
+                key: parent_mint,
+            });
+        }
+        create.invoke_signed(signer_seeds)?;
+
+        // 3. Verify the sub-collection into the root collection
+        if let Some(parent_key) = parent {
+            let (Some(parent_mint), Some(parent_metadata), Some(parent_edition)) = (
+                ctx.accounts.parent_collection_mint.as_ref(),
+                ctx.accounts.parent_collection_metadata.as_ref(),
+                ctx.accounts.parent_collection_master_edition.as_ref(),
+            ) else {
+                return err!(ErrorCode::MissingParentCollection);
+            };
+            require_keys_eq!(parent_mint.key(), parent_key, ErrorCode::MissingParentCollection);
+
+            VerifyCollectionV1CpiBuilder::new(&ctx.accounts.metadata_program)
+                .authority(&ctx.accounts.loot_mint_authority)
+                .metadata(&ctx.accounts.collection_metadata)
+                .collection_mint(parent_mint)
+                .collection_metadata(Some(parent_metadata))
+                .collection_master_edition(Some(parent_edition))
+                .system_program(&ctx.accounts.system_program)
+                .sysvar_instructions(&ctx.accounts.instructions_sysvar)
+                .invoke_signed(signer_seeds)?;
+        }
 
-        emit!(FizzTokenCreatedAdmin {
-            mint: ctx.accounts.token_mint.key(),
-            creator: ctx.accounts.creator.key(),
-            name,
-            symbol,
-            launch_type: FizzLaunchType::AdminUSDC,
-            timestamp: Clock::get()?.unix_timestamp,
-        });
+        let collection = &mut ctx.accounts.loot_collection;
+        collection.mint = ctx.accounts.collection_mint.key();
+        collection.season = season;
+        collection.parent = parent;
+        collection.active = true;
+        collection.bump = ctx.bumps.loot_collection;
 
+        msg!("Loot collection season {} created: {}", season, collection.mint);
         Ok(())
     }
 
-    /// Buy tokens from bonding curve (NO CAPS REQUIRED - anyone can trade!)
-    pub fn fizz_buy(ctx: Context<FizzBuyTokens>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
-        let curve = &mut ctx.accounts.bonding_curve;
-        require!(!curve.graduated, FizzError::TokenGraduated);
-        require!(sol_amount > 0, FizzError::ZeroAmount);
+    /// Open or close a collection for new claims (only authority)
+    pub fn set_loot_collection_active(
+        ctx: Context<ManageLootCollection>,
+        active: bool,
+    ) -> Result<()> {
+        let collection = &mut ctx.accounts.loot_collection;
+        collection.active = active;
 
-        // Calculate fee (1%)
-        let fee = sol_amount.checked_mul(FEE_BPS).unwrap().checked_div(10000).unwrap();
-        let sol_after_fee = sol_amount.checked_sub(fee).unwrap();
+        msg!("Loot collection season {} active: {}", collection.season, active);
+        Ok(())
+    }
 
-        // Calculate tokens out (constant product AMM)
-        let virtual_sol = curve.sol_reserve.checked_add(VIRTUAL_SOL).unwrap();
-        let k = virtual_sol.checked_mul(curve.token_reserve).unwrap();
-        let new_sol = virtual_sol.checked_add(sol_after_fee).unwrap();
-        let new_tokens = k.checked_div(new_sol).unwrap();
-        let tokens_out = curve.token_reserve.checked_sub(new_tokens).unwrap();
+    // ============ LOOT SIGNER REGISTRY ============
 
-        require!(tokens_out >= min_tokens_out, FizzError::SlippageExceeded);
-        require!(tokens_out <= curve.token_reserve, FizzError::InsufficientLiquidity);
+    /// Create the voucher-signer registry (only authority).
+    /// `cluster` is bound into every V2 voucher's domain tag.
+    pub fn init_signer_registry(ctx: Context<InitSignerRegistry>, cluster: LootCluster) -> Result<()> {
+        let registry = &mut ctx.accounts.signer_registry;
+        registry.signers = Vec::new();
+        registry.cluster = cluster;
+        registry.accept_legacy_vouchers = true;
+        registry.bump = ctx.bumps.signer_registry;
 
-        // Transfer SOL from buyer to curve vault
-        anchor_lang::system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.curve_sol_vault.to_account_info(),
-                },
-            ),
-            sol_after_fee,
-        )?;
+        msg!("Voucher signer registry initialized for {:?}", cluster);
+        Ok(())
+    }
 
-        // Transfer fee to treasury
-        anchor_lang::system_program::transfer(
-            CpiContext::new(
-                ctx.accounts.system_program.to_account_info(),
-                anchor_lang::system_program::Transfer {
-                    from: ctx.accounts.buyer.to_account_info(),
-                    to: ctx.accounts.treasury.to_account_info(),
-                },
-            ),
-            fee,
-        )?;
+    /// Allow or reject V1 vouchers (turn off once the V2 rollout is done)
+    pub fn set_legacy_vouchers(ctx: Context<ManageVoucherSigners>, accept: bool) -> Result<()> {
+        ctx.accounts.signer_registry.accept_legacy_vouchers = accept;
 
-        // Transfer tokens to buyer
-        let curve_bump = curve.bump;
-        let token_mint_key = curve.token_mint;
-        token::transfer(
-            CpiContext::new_with_signer(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.curve_token_vault.to_account_info(),
-                    to: ctx.accounts.buyer_token_ata.to_account_info(),
-                    authority: ctx.accounts.bonding_curve.to_account_info(),
-                },
-                &[&[
-                    FIZZ_CURVE_SEEDS,
-                    token_mint_key.as_ref(),
-                    &[curve_bump],
-                ]],
-            ),
-            tokens_out,
-        )?;
+        msg!("Legacy V1 vouchers accepted: {}", accept);
+        Ok(())
+    }
 
-        // Update state
-        curve.sol_reserve = curve.sol_reserve.checked_add(sol_after_fee).unwrap();
-        curve.token_reserve = curve.token_reserve.checked_sub(tokens_out).unwrap();
+    /// Register a voucher-signing key, or reschedule an existing one.
+    /// Rotation = add the new key, then shorten the old key's `valid_until`.
+    pub fn set_voucher_signer(
+        ctx: Context<ManageVoucherSigners>,
+        key: Pubkey,
+        valid_from: i64,
+        valid_until: i64,
+    ) -> Result<()> {
+        require!(valid_from < valid_until, ErrorCode::InvalidSignerWindow);
 
-        let config = &mut ctx.accounts.config;
-        config.total_volume_sol = config.total_volume_sol.checked_add(sol_amount).unwrap();
+        let now = Clock::get()?.unix_timestamp;
+        let registry = &mut ctx.accounts.signer_registry;
 
-        // Check graduation
-        if curve.sol_reserve >= GRADUATION_SOL {
-            emit!(FizzReadyToGraduate {
-                mint: curve.token_mint,
-                sol_raised: curve.sol_reserve,
+        if let Some(signer) = registry.signers.iter_mut().find(|s| s.key == key) {
+            require!(!signer.revoked, ErrorCode::SignerRevoked);
+            signer.valid_from = valid_from;
+            signer.valid_until = valid_until;
+        } else {
+            // Make room by dropping keys that can never sign again
+            if registry.signers.len() >= MAX_VOUCHER_SIGNERS {
+                registry.signers.retain(|s| !s.revoked && s.valid_until > now);
+            }
+            require!(
+                registry.signers.len() < MAX_VOUCHER_SIGNERS,
+                ErrorCode::SignerRegistryFull
+            );
+            registry.signers.push(VoucherSigner {
+                key,
+                valid_from,
+                valid_until,
+                revoked: false,
             });
         }
 
-        emit!(FizzTokenBought {
-            mint: curve.token_mint,
-            buyer: ctx.accounts.buyer.key(),
-            sol_amount: sol_after_fee,
-            tokens_received: tokens_out,
-        });
+        msg!("Voucher signer {} valid [{}, {})", key, valid_from, valid_until);
+        Ok(())
+    }
 
+    /// Revoke a voucher-signing key immediately (only authority)
+    pub fn revoke_voucher_signer(ctx: Context<ManageVoucherSigners>, key: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.signer_registry;
+        let signer = registry
+            .signers
+            .iter_mut()
+            .find(|s| s.key == key)
+            .ok_or(ErrorCode::UnregisteredSigner)?;
+        signer.revoked = true;
+
+        msg!("Voucher signer revoked: {}", key);
         Ok(())
     }
 
-    /// Sell tokens back to curve (NO CAPS REQUIRED)
-    pub fn fizz_sell(ctx: Context<FizzSellTokens>, token_amount: u64, min_sol_out: u64) -> Result<()> {
-        let curve = &mut ctx.accounts.bonding_curve;
-        require!(!curve.graduated, FizzError::TokenGraduated);
-        require!(token_amount > 0, FizzError::ZeroAmount);
+    // ============ LOOT WITNESS BEACONS ============
+
+    /// Create the location beacon registry (only authority)
+    pub fn init_beacon_registry(ctx: Context<InitBeaconRegistry>) -> Result<()> {
+        ctx.accounts.beacon_registry.bump = ctx.bumps.beacon_registry;
+        Ok(())
+    }
 
-        // Calculate SOL out
-        let virtual_sol = curve.sol_reserve.checked_add(VIRTUAL_SOL).unwrap();
-        let k = virtual_sol.checked_mul(curve.token_reserve).unwrap();
-        let new_tokens = curve.token_reserve.checked_add(token_amount).unwrap();
-        let new_sol = k.checked_div(new_tokens).unwrap();
-        let sol_out_gross = virtual_sol.checked_sub(new_sol).unwrap();
-        let sol_out_gross = std::cmp::min(sol_out_gross, curve.sol_reserve);
+    /// Register a beacon key whose attestations count toward rare claims (only authority)
+    pub fn add_beacon(ctx: Context<ManageBeacons>, key: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.beacon_registry;
+        require!(!registry.beacons.contains(&key), ErrorCode::BeaconAlreadyRegistered);
+        require!(registry.beacons.len() < MAX_BEACONS, ErrorCode::BeaconRegistryFull);
+        registry.beacons.push(key);
 
-        // Calculate fee
-        let fee = sol_out_gross.checked_mul(FEE_BPS).unwrap().checked_div(10000).unwrap();
-        let sol_out = sol_out_gross.checked_sub(fee).unwrap();
+        msg!("Beacon registered: {}", key);
+        Ok(())
+    }
 
-        require!(sol_out >= min_sol_out, FizzError::SlippageExceeded);
+    /// Remove a beacon key (only authority)
+    pub fn remove_beacon(ctx: Context<ManageBeacons>, key: Pubkey) -> Result<()> {
+        let registry = &mut ctx.accounts.beacon_registry;
+        let index = registry
+            .beacons
+            .iter()
+            .position(|b| *b == key)
+            .ok_or(ErrorCode::UntrustedWitness)?;
+        registry.beacons.swap_remove(index);
+
+        msg!("Beacon removed: {}", key);
+        Ok(())
+    }
 
-        // Transfer tokens to curve
-        token::transfer(
+    /// Register a player wallet as a witness (only authority). Its attestations
+    /// count only while `LootConfig.accept_player_witnesses` is on.
+    pub fn register_witness(ctx: Context<RegisterWitness>, witness: Pubkey) -> Result<()> {
+        let registration = &mut ctx.accounts.loot_witness;
+        registration.witness = witness;
+        registration.bump = ctx.bumps.loot_witness;
+
+        msg!("Player witness registered: {}", witness);
+        Ok(())
+    }
+
+    /// Deregister a player witness, refunding its rent to the authority (only authority)
+    pub fn remove_witness(ctx: Context<RemoveWitness>) -> Result<()> {
+        msg!("Player witness removed: {}", ctx.accounts.loot_witness.witness);
+        Ok(())
+    }
+
+    // ============ LOOT SALVAGE ============
+
+    /// Create the salvage table, the CAPS reward pool and the crafting-material
+    /// mint (only authority). Anyone can top up the pool with a CAPS transfer.
+    pub fn init_salvage_table(
+        ctx: Context<InitSalvageTable>,
+        payouts: [SalvagePayout; LootRarity::COUNT],
+    ) -> Result<()> {
+        let table = &mut ctx.accounts.salvage_table;
+        table.payouts = payouts;
+        table.bump = ctx.bumps.salvage_table;
+        table.pool_bump = ctx.bumps.salvage_pool;
+
+        msg!("Salvage table created, pool {}", ctx.accounts.salvage_pool.key());
+        Ok(())
+    }
+
+    /// Replace the per-rarity salvage payouts (only authority)
+    pub fn set_salvage_table(
+        ctx: Context<ManageSalvageTable>,
+        payouts: [SalvagePayout; LootRarity::COUNT],
+    ) -> Result<()> {
+        ctx.accounts.salvage_table.payouts = payouts;
+
+        msg!("Salvage table updated");
+        Ok(())
+    }
+
+    /// Burn a loot NFT, close its token account and pay out CAPS from the
+    /// reward pool and/or crafting materials according to its rarity.
+    /// The LootItem stays behind, marked burned.
+    pub fn salvage_loot(ctx: Context<SalvageLoot>) -> Result<()> {
+        ctx.accounts.loot_item.burn()?;
+        let item = &ctx.accounts.loot_item;
+        let payout = ctx.accounts.salvage_table.payout_for(item.roll.rarity);
+
+        // 1. Burn the NFT and close its token account (rent back to the player)
+        burn(
             CpiContext::new(
                 ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.seller_token_ata.to_account_info(),
-                    to: ctx.accounts.curve_token_vault.to_account_info(),
-                    authority: ctx.accounts.seller.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.loot_mint.to_account_info(),
+                    from: ctx.accounts.player_loot_ata.to_account_info(),
+                    authority: ctx.accounts.player.to_account_info(),
                 },
             ),
-            token_amount,
+            1,
         )?;
+        token::close_account(CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: ctx.accounts.player_loot_ata.to_account_info(),
+                destination: ctx.accounts.player.to_account_info(),
+                authority: ctx.accounts.player.to_account_info(),
+            },
+        ))?;
+
+        // 2. CAPS from the reward pool
+        if payout.caps > 0 {
+            require!(
+                ctx.accounts.salvage_pool.amount >= payout.caps,
+                ErrorCode::SalvagePoolEmpty
+            );
+            token::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.salvage_pool.to_account_info(),
+                        to: ctx.accounts.player_caps_ata.to_account_info(),
+                        authority: ctx.accounts.salvage_pool.to_account_info(),
+                    },
+                    &[&[SALVAGE_POOL_SEEDS, &[ctx.accounts.salvage_table.pool_bump]]],
+                ),
+                payout.caps,
+            )?;
+        }
 
-        // Transfer SOL to seller (from PDA)
-        **ctx.accounts.curve_sol_vault.try_borrow_mut_lamports()? -= sol_out;
-        **ctx.accounts.seller.try_borrow_mut_lamports()? += sol_out;
+        // 3. Crafting materials
+        if payout.materials > 0 {
+            token::mint_to(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    MintTo {
+                        mint: ctx.accounts.material_mint.to_account_info(),
+                        to: ctx.accounts.player_material_ata.to_account_info(),
+                        authority: ctx.accounts.loot_mint_authority.to_account_info(),
+                    },
+                    &[&[LOOT_MINT_AUTHORITY_SEEDS, &[ctx.bumps.loot_mint_authority]]],
+                ),
+                payout.materials,
+            )?;
+        }
 
-        // Transfer fee to treasury
-        **ctx.accounts.curve_sol_vault.try_borrow_mut_lamports()? -= fee;
-        **ctx.accounts.treasury.try_borrow_mut_lamports()? += fee;
+        emit!(LootSalvaged {
+            loot_id: item.loot_id,
+            player: ctx.accounts.player.key(),
+            mint: ctx.accounts.loot_mint.key(),
+            rarity: item.roll.rarity,
+            caps_paid: payout.caps,
+            materials_minted: payout.materials,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
 
-        // Update state
-        curve.sol_reserve = curve.sol_reserve.checked_sub(sol_out_gross).unwrap();
-        curve.token_reserve = curve.token_reserve.checked_add(token_amount).unwrap();
+        msg!("Loot #{} salvaged by {}", item.loot_id, ctx.accounts.player.key());
+        Ok(())
+    }
 
-        emit!(FizzTokenSold {
-            mint: curve.token_mint,
-            seller: ctx.accounts.seller.key(),
-            tokens_sold: token_amount,
-            sol_received: sol_out,
+    // ============ LOOT ITEM STATE ============
+
+    /// Create the mutable state (durability, level, mods) for a claimed item.
+    /// Anyone may pay for it; it starts at the configured max durability.
+    pub fn init_item_state(ctx: Context<InitItemState>) -> Result<()> {
+        let item = &ctx.accounts.loot_item;
+        let (core_asset, _) = Pubkey::find_program_address(
+            &[LOOT_ASSET_SEEDS, item.loot_id.to_le_bytes().as_ref()],
+            &crate::ID,
+        );
+        let max_durability = ctx.accounts.loot_config.item_max_durability;
+        ctx.accounts.item_state.set_inner(LootItemState {
+            mint: item.mint,
+            loot_id: item.loot_id,
+            durability: max_durability,
+            max_durability,
+            level: 0,
+            mods: Vec::new(),
+            wear_nonce: 0,
+            is_core_asset: item.mint == core_asset,
+            bump: ctx.bumps.item_state,
         });
 
+        msg!("Item state created for loot #{}", item.loot_id);
         Ok(())
     }
 
-    /// Graduate token to LP (when 85 SOL reached)
-    pub fn fizz_graduate(ctx: Context<FizzGraduate>) -> Result<()> {
-        let curve = &mut ctx.accounts.bonding_curve;
-        require!(!curve.graduated, FizzError::AlreadyGraduated);
-        require!(curve.sol_reserve >= GRADUATION_SOL, FizzError::NotReadyToGraduate);
+    /// Apply a server-signed wear voucher: durability loss, levels and new mods.
+    /// Core assets get their Attributes plugin rewritten to match.
+    pub fn apply_item_wear(ctx: Context<ApplyItemWear>, voucher: LootWearVoucher) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let server_key = ctx.accounts.server_key.key();
+        let wear = &voucher.payload;
+
+        // 1. Voucher checks
+        let registry = &ctx.accounts.signer_registry;
+        registry.check_signer(&server_key, now)?;
+        require!(
+            wear.domain.program_id == crate::ID && wear.domain.cluster == registry.cluster,
+            ErrorCode::VoucherWrongDomain
+        );
+        require!(now < wear.expires_at, ErrorCode::VoucherExpired);
+        require!(
+            wear.mint == ctx.accounts.item_state.mint
+                && wear.loot_id == ctx.accounts.item_state.loot_id,
+            ErrorCode::LootItemMismatch
+        );
+        require!(wear.nonce > ctx.accounts.item_state.wear_nonce, ErrorCode::VoucherNonceUsed);
+        ed25519::verify_signature(
+            &ctx.accounts.instructions_sysvar,
+            &server_key.to_bytes(),
+            &voucher.message()?,
+            &voucher.server_signature,
+        )?;
 
-        // Creator bonus (7%)
-        let creator_bonus = curve.sol_reserve.checked_mul(7).unwrap().checked_div(100).unwrap();
+        // 2. Update the state
+        let state = &mut ctx.accounts.item_state;
+        state.wear_nonce = wear.nonce;
+        state.durability = state.durability.saturating_sub(wear.durability_loss);
+        state.level = state
+            .level
+            .checked_add(wear.levels_gained)
+            .ok_or(ErrorCode::ItemLevelOverflow)?;
+        require!(
+            state.mods.len() + wear.new_mods.len() <= MAX_LOOT_MODS,
+            ErrorCode::TooManyLootMods
+        );
+        state.mods.extend_from_slice(&wear.new_mods);
+
+        // 3. Keep Core attributes in sync
+        sync_core_item_attributes(
+            &CoreItemAccounts {
+                loot_asset: ctx.accounts.loot_asset.as_ref().map(|a| a.to_account_info()),
+                mpl_core_program: ctx.accounts.mpl_core_program.as_ref().map(|p| p.to_account_info()),
+                payer: ctx.accounts.payer.to_account_info(),
+                loot_mint_authority: ctx.accounts.loot_mint_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            ctx.bumps.loot_mint_authority,
+            &ctx.accounts.loot_item.roll,
+            &ctx.accounts.item_state,
+        )?;
 
-        // Transfer bonus to creator
-        **ctx.accounts.curve_sol_vault.try_borrow_mut_lamports()? -= creator_bonus;
-        **ctx.accounts.creator.try_borrow_mut_lamports()? += creator_bonus;
+        let state = &ctx.accounts.item_state;
+        emit!(LootItemWorn {
+            loot_id: state.loot_id,
+            mint: state.mint,
+            durability: state.durability,
+            level: state.level,
+            mods: state.mods.clone(),
+            nonce: state.wear_nonce,
+            signer: server_key,
+            timestamp: now,
+        });
 
-        // Mark graduated
-        curve.graduated = true;
-        curve.graduated_at = Some(Clock::get()?.unix_timestamp);
+        msg!("Loot #{} durability {}/{}", state.loot_id, state.durability, state.max_durability);
+        Ok(())
+    }
 
-        emit!(FizzTokenGraduated {
-            mint: curve.token_mint,
-            creator: curve.creator,
-            sol_raised: curve.sol_reserve,
-            creator_bonus,
+    /// Burn CAPS to restore up to `points` durability. Anyone may repair an item.
+    pub fn repair_item(ctx: Context<RepairItem>, points: u16) -> Result<()> {
+        let state = &mut ctx.accounts.item_state;
+        let restored = points.min(state.max_durability - state.durability);
+        require!(restored > 0, ErrorCode::ItemNotDamaged);
+        let caps_burned = ctx
+            .accounts
+            .loot_config
+            .repair_caps_per_point
+            .checked_mul(restored as u64)
+            .ok_or(ErrorCode::FeeOverflow)?;
+        state.durability += restored;
+
+        if caps_burned > 0 {
+            burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.caps_mint.to_account_info(),
+                        from: ctx.accounts.player_caps_ata.to_account_info(),
+                        authority: ctx.accounts.player.to_account_info(),
+                    },
+                ),
+                caps_burned,
+            )?;
+        }
+
+        sync_core_item_attributes(
+            &CoreItemAccounts {
+                loot_asset: ctx.accounts.loot_asset.as_ref().map(|a| a.to_account_info()),
+                mpl_core_program: ctx.accounts.mpl_core_program.as_ref().map(|p| p.to_account_info()),
+                payer: ctx.accounts.player.to_account_info(),
+                loot_mint_authority: ctx.accounts.loot_mint_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+            ctx.bumps.loot_mint_authority,
+            &ctx.accounts.loot_item.roll,
+            &ctx.accounts.item_state,
+        )?;
+
+        let state = &ctx.accounts.item_state;
+        emit!(LootItemRepaired {
+            loot_id: state.loot_id,
+            mint: state.mint,
+            player: ctx.accounts.player.key(),
+            restored,
+            caps_burned,
+            durability: state.durability,
+            timestamp: Clock::get()?.unix_timestamp,
         });
 
-        msg!("🎓 {} graduated! Creator bonus: {} lamports", curve.symbol, creator_bonus);
+        msg!("Loot #{} repaired +{} for {} CAPS", state.loot_id, restored, caps_burned);
         Ok(())
     }
-}
 
-// ============ HELPER FUNCTIONS ============
+    // ============ LOOT FUSION ============
 
-fn verify_ed25519_signature<'info>(
-    instructions_sysvar: &AccountInfo<'info>,
-    expected_pubkey: &[u8],
-    message: &[u8],
-    signature: &[u8; 64],
-) -> Result<()> {
-    use anchor_lang::solana_program::sysvar::instructions::{
-        load_current_index_checked,
-        load_instruction_at_checked,
-    };
+    /// Create the fusion recipe table (only authority)
+    pub fn init_fusion_recipes(
+        ctx: Context<InitFusionRecipes>,
+        recipes: [FusionRecipe; LootRarity::COUNT],
+    ) -> Result<()> {
+        let table = &mut ctx.accounts.fusion_recipes;
+        table.apply(recipes)?;
+        table.bump = ctx.bumps.fusion_recipes;
+
+        msg!("Fusion recipes created");
+        Ok(())
+    }
 
-    let current_idx = load_current_index_checked(instructions_sysvar)? as usize;
-    if current_idx == 0 {
-        return err!(ErrorCode::NoEd25519Ix);
+    /// Replace the per-rarity fusion recipes (only authority)
+    pub fn set_fusion_recipes(
+        ctx: Context<ManageFusionRecipes>,
+        recipes: [FusionRecipe; LootRarity::COUNT],
+    ) -> Result<()> {
+        ctx.accounts.fusion_recipes.apply(recipes)?;
+
+        msg!("Fusion recipes updated");
+        Ok(())
     }
 
-    let ed_ix = load_instruction_at_checked(current_idx - 1, instructions_sysvar)?;
-    if ed_ix.program_id != anchor_lang::solana_program::ed25519_program::id() {
-        return err!(ErrorCode::InvalidEd25519Program);
+    /// Burn N same-rarity loot NFTs and commit to one of the recipe's output
+    /// rarity, minted by `reveal_fusion` from a later slot.
+    /// Inputs come as remaining accounts, 3 per item in ascending loot id order:
+    /// 0. loot_item  1. loot_mint  2. player_loot_ata.
+    /// `fused_loot_id` must equal `fused_loot_id(burned ids)`. The output roll
+    /// is seeded by the hash of this transaction's slot, which the player can't
+    /// know when signing, so they can't retry until a seed rolls well. Only
+    /// that slot's leader could still bias it.
+    pub fn fuse_loot<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FuseLoot<'info>>,
+        fused_loot_id: u64,
+    ) -> Result<()> {
+        let player = ctx.accounts.player.key();
+        require!(
+            !ctx.remaining_accounts.is_empty() && ctx.remaining_accounts.len().is_multiple_of(3),
+            ErrorCode::FusionAccountsMismatch
+        );
+
+        // 1. Load and check the inputs
+        let mut inputs: Vec<Account<'info, LootItem>> = Vec::new();
+        for accounts in ctx.remaining_accounts.chunks(3) {
+            let item = Account::<LootItem>::try_from(&accounts[0])?;
+            require_keys_eq!(
+                accounts[0].key(),
+                Pubkey::create_program_address(
+                    &[LOOT_ITEM_SEEDS, item.loot_id.to_le_bytes().as_ref(), &[item.bump]],
+                    &crate::ID,
+                )
+                .map_err(|_| ErrorCode::LootItemMismatch)?,
+                ErrorCode::LootItemMismatch
+            );
+            require_keys_eq!(accounts[1].key(), item.mint, ErrorCode::LootItemMismatch);
+            let ata = Account::<TokenAccount>::try_from(&accounts[2])?;
+            require!(
+                ata.mint == item.mint && ata.owner == player && ata.amount == 1,
+                ErrorCode::NotLootOwner
+            );
+            if let Some(prev) = inputs.last() {
+                require!(prev.loot_id < item.loot_id, ErrorCode::FusionInputsUnsorted);
+                require!(prev.roll.rarity == item.roll.rarity, ErrorCode::FusionRarityMismatch);
+            }
+            inputs.push(item);
+        }
+
+        let input_rarity = inputs[0].roll.rarity;
+        let recipe = ctx.accounts.fusion_recipes.recipe_for(input_rarity);
+        require!(recipe.inputs > 0, ErrorCode::FusionRecipeDisabled);
+        require!(inputs.len() == recipe.inputs as usize, ErrorCode::FusionInputCount);
+
+        let burned_ids: Vec<u64> = inputs.iter().map(|i| i.loot_id).collect();
+        require!(fused_loot_id == fused_loot_id_for(&burned_ids), ErrorCode::FusedLootIdMismatch);
+
+        // 2. Burn the NFTs, close their token accounts and mark the item records burned
+        for (item, accounts) in inputs.iter_mut().zip(ctx.remaining_accounts.chunks(3)) {
+            burn(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: accounts[1].clone(),
+                        from: accounts[2].clone(),
+                        authority: ctx.accounts.player.to_account_info(),
+                    },
+                ),
+                1,
+            )?;
+            token::close_account(CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                CloseAccount {
+                    account: accounts[2].clone(),
+                    destination: ctx.accounts.player.to_account_info(),
+                    authority: ctx.accounts.player.to_account_info(),
+                },
+            ))?;
+            item.burn()?;
+            item.exit(&crate::ID)?;
+        }
+
+        // 3. Hold the rolls until the commit slot's hash is known
+        let commit_slot = Clock::get()?.slot;
+        msg!(
+            "Fusing {} {} items into loot #{} at slot {}",
+            inputs.len(),
+            input_rarity.label(),
+            fused_loot_id,
+            commit_slot
+        );
+        ctx.accounts.pending_fusion.set_inner(PendingFusion {
+            player,
+            fused_loot_id,
+            burned_ids,
+            input_rarity,
+            output: recipe.output,
+            rolls: inputs.iter().map(|i| i.roll).collect(),
+            commit_slot,
+            bump: ctx.bumps.pending_fusion,
+        });
+        Ok(())
     }
 
-    let data = ed_ix.data;
-    if data.len() < 16 || data[0] != 1 {
-        return err!(ErrorCode::InvalidEd25519Data);
+    /// Mint the output of a `fuse_loot` (only the fusing player), from any
+    /// later slot. Once the commit slot has left SlotHashes (~512 slots) the
+    /// roll gets no stat boosts, so holding back a reveal never pays off.
+    pub fn reveal_fusion(ctx: Context<RevealFusion>, fused_loot_id: u64) -> Result<()> {
+        let clock = Clock::get()?;
+        let player = ctx.accounts.player.key();
+        let pending = &ctx.accounts.pending_fusion;
+        require!(clock.slot > pending.commit_slot, ErrorCode::FusionNotRevealable);
+        let slot_hash =
+            slot_hash_at(&ctx.accounts.slot_hashes.try_borrow_data()?, pending.commit_slot);
+        let seed = fusion_seed(fused_loot_id, slot_hash);
+        let roll = fuse_roll(&pending.rolls, pending.output, &seed);
+        let burned_ids = pending.burned_ids.clone();
+        let input_rarity = pending.input_rarity;
+
+        let metadata_config = &ctx.accounts.loot_metadata_config;
+        mint_loot_nft(
+            &LootMintAccounts {
+                payer: ctx.accounts.player.to_account_info(),
+                player: ctx.accounts.player.to_account_info(),
+                loot_mint: ctx.accounts.loot_mint.to_account_info(),
+                player_loot_ata: ctx.accounts.player_loot_ata.to_account_info(),
+                loot_metadata: ctx.accounts.loot_metadata.to_account_info(),
+                loot_mint_authority: ctx.accounts.loot_mint_authority.to_account_info(),
+                loot_mint_authority_bump: ctx.bumps.loot_mint_authority,
+                collection_mint: ctx.accounts.collection_mint.to_account_info(),
+                collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+                collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+                instructions_sysvar: ctx.accounts.instructions_sysvar.to_account_info(),
+                metadata_program: ctx.accounts.metadata_program.to_account_info(),
+            },
+            metadata_config,
+            metadata_config.render_fused_name(fused_loot_id, roll.rarity),
+            metadata_config.render_fused_uri(fused_loot_id, roll.rarity),
+        )?;
+
+        ctx.accounts.loot_item.set_inner(LootItem {
+            loot_id: fused_loot_id,
+            mint: ctx.accounts.loot_mint.key(),
+            roll,
+            claimed_by: player,
+            claimed_at: clock.unix_timestamp,
+            location_commitment: None,
+            burned: false,
+            bump: ctx.bumps.loot_item,
+        });
+
+        emit!(LootFused {
+            player,
+            burned_ids,
+            input_rarity,
+            loot_id: fused_loot_id,
+            mint: ctx.accounts.loot_mint.key(),
+            rarity: roll.rarity,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!(
+            "Fused {} {} items into {} loot #{}",
+            ctx.accounts.pending_fusion.rolls.len(),
+            input_rarity.label(),
+            roll.rarity.label(),
+            fused_loot_id
+        );
+        Ok(())
     }
 
-    let pubkey_offset = u16::from_le_bytes([data[2], data[3]]) as usize;
-    let sig_offset = u16::from_le_bytes([data[4], data[5]]) as usize;
-    let msg_offset = u16::from_le_bytes([data[6], data[7]]) as usize;
-    let msg_len = u16::from_le_bytes([data[8], data[9]]) as usize;
+    // ============ FIZZ.FUN: TOKEN LAUNCHPAD ============
 
-    if pubkey_offset + 32 > data.len()
-        || sig_offset + 64 > data.len()
-        || msg_offset + msg_len > data.len()
-    {
-        return err!(ErrorCode::InvalidEd25519Data);
+    /// Initialize Fizz.fun configuration
+    pub fn fizz_init(ctx: Context<FizzInit>) -> Result<()> {
+        let config = &mut ctx.accounts.config;
+        config.authority = ctx.accounts.authority.key();
+        config.treasury = ctx.accounts.treasury.key();
+        config.caps_mint = ctx.accounts.caps_mint.key();
+        config.total_tokens_launched = 0;
+        config.total_volume_sol = 0;
+        config.total_caps_burned = 0;
+        config.admin_usdc_launches = 0;
+        config.params = FizzLaunchParams::DEFAULT;
+        config.launch_tiers = FizzLaunchTier::defaults();
+        config.bump = ctx.bumps.config;
+        
+        msg!("Fizz.fun initialized! Treasury: {}", config.treasury);
+        Ok(())
     }
 
-    if &data[pubkey_offset..pubkey_offset + 32] != expected_pubkey {
-        return err!(ErrorCode::WrongPubkey);
+    /// Retune the launchpad (only authority). Existing curves keep the
+    /// parameters they snapshotted at creation. `admin_fee_bps` and
+    /// `admin_creator_bonus_bps` only apply to `fizz_create_token_admin`
+    /// curves; CAPS launches take their fee and bonus from the tier table,
+    /// set with `fizz_set_launch_tiers`.
+    pub fn fizz_update_config(ctx: Context<FizzManageConfig>, params: FizzLaunchParams) -> Result<()> {
+        params.validate()?;
+        ctx.accounts.config.params = params;
+
+        emit!(FizzConfigUpdated {
+            authority: ctx.accounts.authority.key(),
+            params,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
     }
-    if &data[sig_offset..sig_offset + 64] != signature {
-        return err!(ErrorCode::WrongSignature);
+
+    /// Replace the CAPS launch tier table (only authority). Tiers are
+    /// ordered by ascending `min_caps`; a launch gets the highest it qualifies for.
+    pub fn fizz_set_launch_tiers(
+        ctx: Context<FizzManageConfig>,
+        tiers: Vec<FizzLaunchTier>,
+    ) -> Result<()> {
+        FizzLaunchTier::validate_table(&tiers)?;
+        ctx.accounts.config.launch_tiers = tiers.clone();
+
+        emit!(FizzLaunchTiersUpdated {
+            authority: ctx.accounts.authority.key(),
+            tiers,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+        Ok(())
     }
-    if &data[msg_offset..msg_offset + msg_len] != message {
-        return err!(ErrorCode::WrongMessage);
+
+    /// Add an admin (only authority)
+    pub fn fizz_add_admin(ctx: Context<FizzManageAdmin>, admin: Pubkey) -> Result<()> {
+        let admin_record = &mut ctx.accounts.admin_record;
+        admin_record.admin = admin;
+        admin_record.added_at = Clock::get()?.unix_timestamp;
+        admin_record.is_active = true;
+        admin_record.bump = ctx.bumps.admin_record;
+        
+        msg!("Fizz.fun admin added: {}", admin);
+        Ok(())
     }
 
-    Ok(())
-}
+    /// Launch a new token (requires the lowest launch tier's `min_caps` CAPS)
+    /// Name/symbol/uri emitted in event for off-chain indexing (saves rent!)
+    pub fn fizz_create_token(
+        ctx: Context<FizzCreateToken>,
+        name: String,
+        symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        // Validate inputs
+        require!(name.len() <= 32, FizzError::NameTooLong);
+        require!(symbol.len() <= 10, FizzError::SymbolTooLong);
+        require!(uri.len() <= 200, FizzError::UriTooLong);
 
-// ============ DATA STRUCTURES ============
+        // 1-2. Resolve the launch tier from the CAPS balance (fee and perks)
+        let params = ctx.accounts.config.params;
+        let tier = ctx
+            .accounts
+            .config
+            .launch_tier_for(ctx.accounts.creator_caps_ata.amount)
+            .ok_or(FizzError::InsufficientCapsToLaunch)?;
+        let launch_fee = tier.launch_fee;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
-pub struct LootVoucher {
-    pub loot_id: u64,
-    pub latitude: f64,
-    pub longitude: f64,
-    pub timestamp: i64,
-    pub location_hint: String,
-    pub server_signature: [u8; 64],
-}
+        // 3. Burn CAPS launch fee
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.caps_mint.to_account_info(),
+                    from: ctx.accounts.creator_caps_ata.to_account_info(),
+                    authority: ctx.accounts.creator.to_account_info(),
+                },
+            ),
+            launch_fee,
+        )?;
+
+        // 4. Initialize bonding curve (minimal on-chain data for cheap rent)
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.creator = ctx.accounts.creator.key();
+        curve.token_mint = ctx.accounts.token_mint.key();
+        curve.sol_reserve = 0;
+        curve.graduated = false;
+        curve.created_at = Clock::get()?.unix_timestamp;
+        curve.snapshot(&params);
+        curve.apply_tier(&tier);
+        curve.token_reserve = params.curve_supply(curve.creator_bonus_bps)?;
+        curve.bump = ctx.bumps.bonding_curve;
+        fund_sol_vault(
+            &ctx.accounts.rent,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.curve_sol_vault,
+        )?;
+
+        // 5. Mint total supply to curve vault
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.curve_token_vault.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[&[
+                    FIZZ_CURVE_SEEDS,
+                    ctx.accounts.token_mint.key().as_ref(),
+                    &[ctx.bumps.bonding_curve],
+                ]],
+            ),
+            TOTAL_SUPPLY,
+        )?;
+
+        // 6. Update config
+        let config = &mut ctx.accounts.config;
+        config.total_tokens_launched += 1;
+        config.total_caps_burned += launch_fee;
+
+        emit!(FizzTokenCreated {
+            mint: ctx.accounts.token_mint.key(),
+            creator: ctx.accounts.creator.key(),
+            name,
+            symbol,
+            launch_type: tier.launch_type,
+            caps_burned: launch_fee,
+            fee_bps: tier.fee_bps,
+            creator_bonus_bps: tier.creator_bonus_bps,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Admin launch: `AdminUSDC` (pre-mainnet bootstrap, USDC paid off-chain)
+    /// or `AdminFree` (official tokens). Uses the config's admin trading fee and bonus.
+    pub fn fizz_create_token_admin(
+        ctx: Context<FizzCreateTokenAdmin>,
+        name: String,
+        symbol: String,
+        uri: String,
+        launch_type: FizzLaunchType,
+    ) -> Result<()> {
+        require!(name.len() <= 32, FizzError::NameTooLong);
+        require!(symbol.len() <= 10, FizzError::SymbolTooLong);
+        require!(uri.len() <= 200, FizzError::UriTooLong);
+        require!(ctx.accounts.admin_record.is_active, FizzError::AdminInactive);
+        require!(
+            matches!(launch_type, FizzLaunchType::AdminUSDC | FizzLaunchType::AdminFree),
+            FizzError::InvalidLaunchType
+        );
+
+        // Initialize bonding curve (minimal on-chain data, no CAPS burn for admin)
+        let params = ctx.accounts.config.params;
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.creator = ctx.accounts.creator.key();
+        curve.token_mint = ctx.accounts.token_mint.key();
+        curve.sol_reserve = 0;
+        curve.graduated = false;
+        curve.created_at = Clock::get()?.unix_timestamp;
+        curve.launch_type = launch_type; // Clearly marked!
+        curve.snapshot(&params);
+        curve.token_reserve = params.curve_supply(curve.creator_bonus_bps)?;
+        curve.bump = ctx.bumps.bonding_curve;
+        fund_sol_vault(
+            &ctx.accounts.rent,
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.creator.to_account_info(),
+            &ctx.accounts.curve_sol_vault,
+        )?;
+
+        // Mint total supply to curve vault
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.token_mint.to_account_info(),
+                    to: ctx.accounts.curve_token_vault.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[&[
+                    FIZZ_CURVE_SEEDS,
+                    ctx.accounts.token_mint.key().as_ref(),
+                    &[ctx.bumps.bonding_curve],
+                ]],
+            ),
+            TOTAL_SUPPLY,
+        )?;
+
+        // Update config
+        let config = &mut ctx.accounts.config;
+        config.total_tokens_launched += 1;
+        if launch_type == FizzLaunchType::AdminUSDC {
+            config.admin_usdc_launches += 1;
+        }
+
+        emit!(FizzTokenCreatedAdmin {
+            mint: ctx.accounts.token_mint.key(),
+            creator: ctx.accounts.creator.key(),
+            name,
+            symbol,
+            launch_type,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        Ok(())
+    }
+
+    /// Buy tokens from bonding curve (NO CAPS REQUIRED - anyone can trade!)
+    pub fn fizz_buy(ctx: Context<FizzBuyTokens>, sol_amount: u64, min_tokens_out: u64) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
+        require!(!curve.graduated, FizzError::TokenGraduated);
+        require!(sol_amount > 0, FizzError::ZeroAmount);
+
+        // Quote with fee (constant product AMM, u128 math)
+        let BuyQuote {
+            fee,
+            sol_in: sol_after_fee,
+            tokens_out,
+        } = curve_math::quote_buy(curve.reserves(), sol_amount, curve.fee_bps).map_err(FizzError::from)?;
+
+        require!(tokens_out >= min_tokens_out, FizzError::SlippageExceeded);
+        require!(tokens_out < curve.token_reserve, FizzError::InsufficientLiquidity);
+
+        // Transfer SOL from buyer to curve vault
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.curve_sol_vault.to_account_info(),
+                },
+            ),
+            sol_after_fee,
+        )?;
+
+        // Transfer fee to treasury
+        anchor_lang::system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+
+        // Transfer tokens to buyer
+        let curve_bump = ctx.accounts.bonding_curve.bump;
+        let token_mint_key = ctx.accounts.bonding_curve.token_mint;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.curve_token_vault.to_account_info(),
+                    to: ctx.accounts.buyer_token_ata.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[&[
+                    FIZZ_CURVE_SEEDS,
+                    token_mint_key.as_ref(),
+                    &[curve_bump],
+                ]],
+            ),
+            tokens_out,
+        )?;
+
+        // Update state
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.sol_reserve = curve
+            .sol_reserve
+            .checked_add(sol_after_fee)
+            .ok_or(FizzError::MathOverflow)?;
+        curve.token_reserve = curve
+            .token_reserve
+            .checked_sub(tokens_out)
+            .ok_or(FizzError::MathOverflow)?;
+
+        let config = &mut ctx.accounts.config;
+        config.total_volume_sol = config
+            .total_volume_sol
+            .checked_add(sol_amount)
+            .ok_or(FizzError::MathOverflow)?;
+
+        // Check graduation
+        if curve.sol_reserve >= curve.graduation_sol {
+            emit!(FizzReadyToGraduate {
+                mint: curve.token_mint,
+                sol_raised: curve.sol_reserve,
+            });
+        }
+
+        emit!(FizzTokenBought {
+            mint: curve.token_mint,
+            buyer: ctx.accounts.buyer.key(),
+            sol_amount: sol_after_fee,
+            tokens_received: tokens_out,
+        });
+
+        Ok(())
+    }
+
+    /// Sell tokens back to curve (NO CAPS REQUIRED)
+    pub fn fizz_sell(ctx: Context<FizzSellTokens>, token_amount: u64, min_sol_out: u64) -> Result<()> {
+        // Quote SOL out and fee (u128 math, capped at real SOL) and update reserves
+        let SellQuote { fee, sol_out, .. } = ctx.accounts.bonding_curve.sell(token_amount)?;
+        require!(sol_out >= min_sol_out, FizzError::SlippageExceeded);
+
+        // Transfer tokens to curve
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.seller_token_ata.to_account_info(),
+                    to: ctx.accounts.curve_token_vault.to_account_info(),
+                    authority: ctx.accounts.seller.to_account_info(),
+                },
+            ),
+            token_amount,
+        )?;
+
+        // SOL to seller and fee to treasury, signed by the system-owned vault PDA
+        let token_mint_key = ctx.accounts.bonding_curve.token_mint;
+        let vault_seeds: &[&[u8]] = &[
+            FIZZ_SOL_VAULT_SEEDS,
+            token_mint_key.as_ref(),
+            &[ctx.bumps.curve_sol_vault],
+        ];
+        for (to, lamports) in [
+            (ctx.accounts.seller.to_account_info(), sol_out),
+            (ctx.accounts.treasury.to_account_info(), fee),
+        ] {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.curve_sol_vault.to_account_info(),
+                        to,
+                    },
+                    &[vault_seeds],
+                ),
+                lamports,
+            )?;
+        }
+
+        emit!(FizzTokenSold {
+            mint: token_mint_key,
+            seller: ctx.accounts.seller.key(),
+            tokens_sold: token_amount,
+            sol_received: sol_out,
+        });
+
+        Ok(())
+    }
+
+    /// Graduate token to LP (when `graduation_sol` is reached). Anyone can
+    /// crank it. After the creator bonus, the curve's SOL and its non-curve
+    /// tokens seed a `FizzPool`; unsold curve tokens are burned and the
+    /// initial LP tokens are locked in a vault owned by the pool.
+    pub fn fizz_graduate(ctx: Context<FizzGraduate>) -> Result<()> {
+        let curve = &ctx.accounts.bonding_curve;
+        require!(!curve.graduated, FizzError::AlreadyGraduated);
+        require!(curve.sol_reserve >= curve.graduation_sol, FizzError::NotReadyToGraduate);
+
+        // Creator bonus (snapshotted from the launch tier)
+        let creator_bonus = curve
+            .sol_reserve
+            .checked_mul(curve.creator_bonus_bps)
+            .ok_or(FizzError::MathOverflow)?
+            / curve_math::BPS_DENOMINATOR;
+        let sol_liquidity = curve.sol_reserve - creator_bonus;
+        let unsold_tokens = curve.token_reserve;
+        let token_liquidity = ctx
+            .accounts
+            .curve_token_vault
+            .amount
+            .checked_sub(unsold_tokens)
+            .ok_or(FizzError::MathOverflow)?;
+        require!(
+            sol_liquidity > 0 && token_liquidity > 0,
+            FizzError::InsufficientLiquidity
+        );
+        let lp_locked =
+            curve_math::initial_lp(sol_liquidity, token_liquidity).map_err(FizzError::from)?;
+
+        let token_mint_key = curve.token_mint;
+        let curve_seeds: &[&[u8]] = &[FIZZ_CURVE_SEEDS, token_mint_key.as_ref(), &[curve.bump]];
+        let vault_seeds: &[&[u8]] = &[
+            FIZZ_SOL_VAULT_SEEDS,
+            token_mint_key.as_ref(),
+            &[ctx.bumps.curve_sol_vault],
+        ];
+        let pool_seeds: &[&[u8]] = &[FIZZ_POOL_SEEDS, token_mint_key.as_ref(), &[ctx.bumps.pool]];
+
+        // 1. SOL: bonus to creator, the rest into the pool account itself
+        for (to, lamports) in [
+            (ctx.accounts.creator.to_account_info(), creator_bonus),
+            (ctx.accounts.pool.to_account_info(), sol_liquidity),
+        ] {
+            anchor_lang::system_program::transfer(
+                CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    anchor_lang::system_program::Transfer {
+                        from: ctx.accounts.curve_sol_vault.to_account_info(),
+                        to,
+                    },
+                    &[vault_seeds],
+                ),
+                lamports,
+            )?;
+        }
+
+        // 2. Tokens: non-curve supply into the pool, unsold curve tokens burned
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.curve_token_vault.to_account_info(),
+                    to: ctx.accounts.pool_token_vault.to_account_info(),
+                    authority: ctx.accounts.bonding_curve.to_account_info(),
+                },
+                &[curve_seeds],
+            ),
+            token_liquidity,
+        )?;
+        if unsold_tokens > 0 {
+            token::burn(
+                CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Burn {
+                        mint: ctx.accounts.token_mint.to_account_info(),
+                        from: ctx.accounts.curve_token_vault.to_account_info(),
+                        authority: ctx.accounts.bonding_curve.to_account_info(),
+                    },
+                    &[curve_seeds],
+                ),
+                unsold_tokens,
+            )?;
+        }
+
+        // 3. Initial LP, locked for good: only the pool can move it and it never does
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.locked_lp_vault.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[pool_seeds],
+            ),
+            lp_locked,
+        )?;
+
+        // 4. Record the pool and mark graduated
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts.pool.set_inner(FizzPool {
+            token_mint: token_mint_key,
+            lp_mint: ctx.accounts.lp_mint.key(),
+            token_vault: ctx.accounts.pool_token_vault.key(),
+            sol_reserve: sol_liquidity,
+            token_reserve: token_liquidity,
+            created_at: now,
+            bump: ctx.bumps.pool,
+        });
+        let initial_price =
+            curve_math::spot_price(ctx.accounts.pool.reserves()).map_err(FizzError::from)?;
+
+        let curve = &mut ctx.accounts.bonding_curve;
+        curve.graduated = true;
+
+        emit!(FizzTokenGraduated {
+            mint: curve.token_mint,
+            creator: curve.creator,
+            sol_raised: curve.sol_reserve,
+            creator_bonus,
+        });
+        emit!(FizzLiquidityMigrated {
+            mint: token_mint_key,
+            pool: ctx.accounts.pool.key(),
+            lp_mint: ctx.accounts.lp_mint.key(),
+            sol_liquidity,
+            token_liquidity,
+            tokens_burned: unsold_tokens,
+            lp_locked,
+            initial_price,
+            timestamp: now,
+        });
+
+        msg!(
+            "🎓 {} graduated! Creator bonus: {} lamports, pool {}",
+            token_mint_key,
+            creator_bonus,
+            ctx.accounts.pool.key()
+        );
+        Ok(())
+    }
+
+    // ============ FIZZ.FUN: GRADUATED POOLS ============
+
+    /// Create the CAPS buyback bucket that collects its share of pool fees (only authority)
+    pub fn fizz_init_buyback(ctx: Context<FizzInitBuyback>) -> Result<()> {
+        ctx.accounts.buyback.total_accrued = 0;
+        ctx.accounts.buyback.bump = ctx.bumps.buyback;
+
+        msg!("Buyback bucket created: {}", ctx.accounts.buyback.key());
+        Ok(())
+    }
+
+    /// Withdraw buyback SOL to the authority to execute a CAPS buyback (only authority)
+    pub fn fizz_withdraw_buyback(ctx: Context<FizzWithdrawBuyback>, amount: u64) -> Result<()> {
+        let buyback = ctx.accounts.buyback.to_account_info();
+        let rent_floor = Rent::get()?.minimum_balance(buyback.data_len());
+        require!(
+            buyback.lamports().saturating_sub(rent_floor) >= amount,
+            FizzError::InsufficientLiquidity
+        );
+        **buyback.try_borrow_mut_lamports()? -= amount;
+        **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
+
+        msg!("Withdrew {} lamports for CAPS buyback", amount);
+        Ok(())
+    }
+
+    /// Swap against a graduated token's pool. The fee is always taken in SOL
+    /// and split between LPs, the treasury and the buyback bucket.
+    pub fn pool_swap(
+        ctx: Context<PoolSwap>,
+        direction: FizzSwapDirection,
+        amount_in: u64,
+        min_amount_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline, FizzError::DeadlineExpired);
+        require!(amount_in > 0, FizzError::ZeroAmount);
+
+        let params = ctx.accounts.config.params;
+        let reserves = ctx.accounts.pool.reserves();
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        let pool_seeds: &[&[u8]] =
+            &[FIZZ_POOL_SEEDS, token_mint_key.as_ref(), &[ctx.accounts.pool.bump]];
+
+        let (amount_out, split) = match direction {
+            FizzSwapDirection::Buy => {
+                let quote = curve_math::quote_buy(reserves, amount_in, params.pool_fee_bps)
+                    .map_err(FizzError::from)?;
+                let split = curve_math::split_fee(
+                    quote.fee,
+                    params.pool_treasury_share_bps,
+                    params.pool_buyback_share_bps,
+                )
+                .map_err(FizzError::from)?;
+                require!(quote.tokens_out >= min_amount_out, FizzError::SlippageExceeded);
+                require!(
+                    quote.tokens_out < reserves.token_reserve,
+                    FizzError::InsufficientLiquidity
+                );
+
+                // SOL in (plus the LP share of the fee), fee cuts out
+                let system_program = ctx.accounts.system_program.to_account_info();
+                let trader = ctx.accounts.trader.to_account_info();
+                for (to, lamports) in [
+                    (ctx.accounts.pool.to_account_info(), quote.sol_in + split.lp),
+                    (ctx.accounts.treasury.to_account_info(), split.treasury),
+                    (ctx.accounts.buyback.to_account_info(), split.buyback),
+                ] {
+                    system_transfer(&system_program, &trader, &to, lamports)?;
+                }
+
+                token::transfer(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.pool_token_vault.to_account_info(),
+                            to: ctx.accounts.trader_token_ata.to_account_info(),
+                            authority: ctx.accounts.pool.to_account_info(),
+                        },
+                        &[pool_seeds],
+                    ),
+                    quote.tokens_out,
+                )?;
+
+                let pool = &mut ctx.accounts.pool;
+                pool.sol_reserve = pool
+                    .sol_reserve
+                    .checked_add(quote.sol_in + split.lp)
+                    .ok_or(FizzError::MathOverflow)?;
+                pool.token_reserve -= quote.tokens_out;
+                (quote.tokens_out, split)
+            }
+            FizzSwapDirection::Sell => {
+                let quote = curve_math::quote_sell(reserves, amount_in, params.pool_fee_bps)
+                    .map_err(FizzError::from)?;
+                let split = curve_math::split_fee(
+                    quote.fee,
+                    params.pool_treasury_share_bps,
+                    params.pool_buyback_share_bps,
+                )
+                .map_err(FizzError::from)?;
+                require!(quote.sol_out >= min_amount_out, FizzError::SlippageExceeded);
+
+                token::transfer(
+                    CpiContext::new(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: ctx.accounts.trader_token_ata.to_account_info(),
+                            to: ctx.accounts.pool_token_vault.to_account_info(),
+                            authority: ctx.accounts.trader.to_account_info(),
+                        },
+                    ),
+                    amount_in,
+                )?;
+
+                // SOL out of the pool account; the LP share of the fee stays in
+                let sol_leaving = quote.sol_out + split.treasury + split.buyback;
+                **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= sol_leaving;
+                **ctx.accounts.trader.try_borrow_mut_lamports()? += quote.sol_out;
+                **ctx.accounts.treasury.try_borrow_mut_lamports()? += split.treasury;
+                **ctx.accounts.buyback.to_account_info().try_borrow_mut_lamports()? +=
+                    split.buyback;
+
+                let pool = &mut ctx.accounts.pool;
+                pool.sol_reserve -= sol_leaving;
+                pool.token_reserve = pool
+                    .token_reserve
+                    .checked_add(amount_in)
+                    .ok_or(FizzError::MathOverflow)?;
+                (quote.sol_out, split)
+            }
+        };
+
+        let buyback = &mut ctx.accounts.buyback;
+        buyback.total_accrued = buyback.total_accrued.saturating_add(split.buyback);
+
+        emit!(FizzPoolSwapped {
+            mint: token_mint_key,
+            pool: ctx.accounts.pool.key(),
+            trader: ctx.accounts.trader.key(),
+            direction,
+            amount_in,
+            amount_out,
+            lp_fee: split.lp,
+            treasury_fee: split.treasury,
+            buyback_fee: split.buyback,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Deposit `sol_amount` plus tokens at the pool's current ratio for LP tokens
+    pub fn pool_add_liquidity(
+        ctx: Context<PoolLiquidity>,
+        sol_amount: u64,
+        max_token_amount: u64,
+        min_lp_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline, FizzError::DeadlineExpired);
+        require!(sol_amount > 0, FizzError::ZeroAmount);
+
+        let quote = curve_math::quote_deposit(
+            ctx.accounts.pool.reserves(),
+            ctx.accounts.lp_mint.supply,
+            sol_amount,
+        )
+        .map_err(FizzError::from)?;
+        require!(quote.tokens_in <= max_token_amount, FizzError::SlippageExceeded);
+        require!(quote.lp_out >= min_lp_out && quote.lp_out > 0, FizzError::SlippageExceeded);
+
+        system_transfer(
+            &ctx.accounts.system_program.to_account_info(),
+            &ctx.accounts.provider.to_account_info(),
+            &ctx.accounts.pool.to_account_info(),
+            sol_amount,
+        )?;
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.provider_token_ata.to_account_info(),
+                    to: ctx.accounts.pool_token_vault.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            quote.tokens_in,
+        )?;
+
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    to: ctx.accounts.provider_lp_ata.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[&[FIZZ_POOL_SEEDS, token_mint_key.as_ref(), &[ctx.accounts.pool.bump]]],
+            ),
+            quote.lp_out,
+        )?;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.sol_reserve = pool
+            .sol_reserve
+            .checked_add(sol_amount)
+            .ok_or(FizzError::MathOverflow)?;
+        pool.token_reserve = pool
+            .token_reserve
+            .checked_add(quote.tokens_in)
+            .ok_or(FizzError::MathOverflow)?;
+
+        emit!(FizzLiquidityAdded {
+            mint: token_mint_key,
+            provider: ctx.accounts.provider.key(),
+            sol_amount,
+            token_amount: quote.tokens_in,
+            lp_minted: quote.lp_out,
+            timestamp: now,
+        });
+        Ok(())
+    }
+
+    /// Burn LP tokens for a pro-rata share of the pool's SOL and tokens
+    pub fn pool_remove_liquidity(
+        ctx: Context<PoolLiquidity>,
+        lp_amount: u64,
+        min_sol_out: u64,
+        min_tokens_out: u64,
+        deadline: i64,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(now <= deadline, FizzError::DeadlineExpired);
+        require!(lp_amount > 0, FizzError::ZeroAmount);
+
+        let quote = curve_math::quote_withdrawal(
+            ctx.accounts.pool.reserves(),
+            ctx.accounts.lp_mint.supply,
+            lp_amount,
+        )
+        .map_err(FizzError::from)?;
+        require!(quote.sol_out >= min_sol_out, FizzError::SlippageExceeded);
+        require!(quote.tokens_out >= min_tokens_out, FizzError::SlippageExceeded);
+
+        token::burn(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Burn {
+                    mint: ctx.accounts.lp_mint.to_account_info(),
+                    from: ctx.accounts.provider_lp_ata.to_account_info(),
+                    authority: ctx.accounts.provider.to_account_info(),
+                },
+            ),
+            lp_amount,
+        )?;
+
+        let token_mint_key = ctx.accounts.pool.token_mint;
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.pool_token_vault.to_account_info(),
+                    to: ctx.accounts.provider_token_ata.to_account_info(),
+                    authority: ctx.accounts.pool.to_account_info(),
+                },
+                &[&[FIZZ_POOL_SEEDS, token_mint_key.as_ref(), &[ctx.accounts.pool.bump]]],
+            ),
+            quote.tokens_out,
+        )?;
+        **ctx.accounts.pool.to_account_info().try_borrow_mut_lamports()? -= quote.sol_out;
+        **ctx.accounts.provider.try_borrow_mut_lamports()? += quote.sol_out;
+
+        let pool = &mut ctx.accounts.pool;
+        pool.sol_reserve -= quote.sol_out;
+        pool.token_reserve -= quote.tokens_out;
+
+        emit!(FizzLiquidityRemoved {
+            mint: token_mint_key,
+            provider: ctx.accounts.provider.key(),
+            sol_amount: quote.sol_out,
+            token_amount: quote.tokens_out,
+            lp_burned: lp_amount,
+            timestamp: now,
+        });
+        Ok(())
+    }
+}
+
+// ============ HELPER FUNCTIONS ============
+
+/// System-program SOL transfer from a signer; no-op for 0 lamports
+fn system_transfer<'info>(
+    system_program: &AccountInfo<'info>,
+    from: &AccountInfo<'info>,
+    to: &AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: from.clone(),
+                to: to.clone(),
+            },
+        ),
+        lamports,
+    )
+}
+
+/// Lamports a curve's SOL vault needs on top of `lamports` to be rent-exempt
+fn sol_vault_rent_top_up(rent: &Rent, lamports: u64) -> u64 {
+    rent.minimum_balance(0).saturating_sub(lamports)
+}
+
+/// Fund a new curve's system-owned SOL vault to rent exemption. The floor is
+/// never counted in `sol_reserve`, so buys, sells and graduation move only
+/// reserve lamports and can't leave the vault below rent exemption.
+fn fund_sol_vault<'info>(
+    rent: &Rent,
+    system_program: &AccountInfo<'info>,
+    creator: &AccountInfo<'info>,
+    vault: &AccountInfo<'info>,
+) -> Result<()> {
+    system_transfer(system_program, creator, vault, sol_vault_rent_top_up(rent, vault.lamports()))
+}
+
+/// What `prepare_claim` hands to the path-specific mint step
+struct PreparedClaim {
+    roll: LootRoll,
+    now: i64,
+    /// CAPS burned (0 for free daily claims and SOL payments)
+    fee_burned: u64,
+}
+
+/// Steps 1-3 of every single-voucher claim: claim mode, voucher and geocell
+/// checks, server and witness signatures, then the fee (daily free claims
+/// first) in CAPS with a burn/treasury split or in SOL, plus rent
+/// reimbursement if a relayer sponsored the claim
+fn prepare_claim(
+    claim: &mut ClaimLootCommon,
+    bumps: &ClaimLootCommonBumps,
+    voucher: &LootVoucher,
+    mode: LootClaimMode,
+    payment: LootPaymentMethod,
+    attestations: &[WitnessAttestation],
+    witness_accounts: &[AccountInfo],
+) -> Result<PreparedClaim> {
+    let roll = voucher.payload.roll();
+
+    // 1. Check claim mode, signer, player binding, expiry, nonce, domain and geocell quota
+    require!(
+        claim.loot_config.resolve_claim_mode(&voucher.payload)? == mode,
+        ErrorCode::WrongClaimMode
+    );
+    let now = Clock::get()?.unix_timestamp;
+    if claim.player_state.player == Pubkey::default() {
+        claim.player_state.player = claim.player.key();
+        claim.player_state.bump = bumps.player_state;
+    }
+    validate_voucher(
+        voucher,
+        &claim.signer_registry,
+        &mut claim.player_state,
+        &claim.player.key(),
+        &claim.server_key.key(),
+        now,
+    )?;
+    claim.location_cell.prefix = claim.loot_config.cell_key(&voucher.payload);
+    claim.location_cell.bump = bumps.location_cell;
+    claim.cell_cooldown.bump = bumps.cell_cooldown;
+    record_cell_claim(
+        &claim.loot_config,
+        &mut claim.location_cell,
+        &mut claim.cell_cooldown,
+        now,
+    )?;
+
+    // 2. Verify server Ed25519 signature over the unsigned payload, then witnesses
+    ed25519::verify_signature(
+        &claim.instructions_sysvar,
+        &claim.server_key.key().to_bytes(),
+        &voucher.message()?,
+        &voucher.server_signature,
+    )?;
+    let registered = registered_witnesses(witness_accounts)?;
+    let excluded = [claim.player.key(), claim.server_key.key()];
+    verify_witnesses(
+        &claim.loot_config,
+        &claim.instructions_sysvar,
+        voucher,
+        attestations,
+        |witness| {
+            witness_trusted(
+                &claim.loot_config,
+                &claim.beacon_registry,
+                &registered,
+                &excluded,
+                witness,
+            )
+        },
+        now,
+    )?;
+
+    // 3. Charge the fee
+    let fee_amount = loot_fee_due(&claim.loot_config, &mut claim.player_state, roll.rarity, now)?;
+    let (fee_burned, mut fee_to_treasury) = claim.loot_config.split_fee(fee_amount);
+    fee_to_treasury = fee_to_treasury
+        .checked_add(claim.loot_config.rent_reimbursement(
+            &claim.fee_payer.key(),
+            &claim.player.key(),
+            1,
+        )?)
+        .ok_or(ErrorCode::FeeOverflow)?;
+    let fee_burned = charge_claim_fee(
+        &ClaimFeeAccounts {
+            token_program: claim.token_program.to_account_info(),
+            caps_mint: claim.caps_mint.to_account_info(),
+            player_caps_ata: claim.player_caps_ata.to_account_info(),
+            treasury_caps_ata: claim.treasury_caps_ata.to_account_info(),
+            player: claim.player.to_account_info(),
+            sol_treasury: claim.sol_treasury.to_account_info(),
+            system_program: claim.system_program.to_account_info(),
+        },
+        &claim.loot_config,
+        payment,
+        fee_burned,
+        fee_to_treasury,
+    )?;
+
+    Ok(PreparedClaim {
+        roll,
+        now,
+        fee_burned,
+    })
+}
+
+/// Step 5 of every single-voucher claim: write the LootItem that reserves
+/// the loot id, and build the `LootClaimed` event for it
+fn record_claim(
+    claim: &ClaimLootCommon,
+    voucher: &LootVoucher,
+    prepared: &PreparedClaim,
+    payment: LootPaymentMethod,
+    mint: Pubkey,
+) -> Result<LootClaimed> {
+    let loot_id = voucher.payload.loot_id();
+    reserve_loot_item(
+        &claim.fee_payer.to_account_info(),
+        &claim.system_program.to_account_info(),
+        &claim.loot_item.to_account_info(),
+        LootItem {
+            loot_id,
+            mint,
+            roll: prepared.roll,
+            claimed_by: claim.player.key(),
+            claimed_at: prepared.now,
+            location_commitment: voucher.payload.location_commitment(),
+            burned: false,
+            bump: 0,
+        },
+    )?;
+
+    let coordinates = voucher.payload.coordinates();
+    Ok(LootClaimed {
+        loot_id,
+        player: claim.player.key(),
+        mint,
+        payment,
+        fee_burned: prepared.fee_burned,
+        signer: claim.server_key.key(),
+        lat_micro: coordinates.map(|(lat, _)| lat),
+        lon_micro: coordinates.map(|(_, lon)| lon),
+        location_commitment: voucher.payload.location_commitment(),
+        timestamp: prepared.now,
+    })
+}
+
+/// Voucher checks that don't need the Ed25519 instruction
+fn validate_voucher(
+    voucher: &LootVoucher,
+    registry: &SignerRegistry,
+    player_state: &mut LootPlayer,
+    player: &Pubkey,
+    server_key: &Pubkey,
+    now: i64,
+) -> Result<()> {
+    registry.check_signer(server_key, now)?;
+
+    match &voucher.payload {
+        LootVoucherPayload::V1(v) => {
+            require!(registry.accept_legacy_vouchers, ErrorCode::LegacyVoucherDisabled);
+            geo::validate(geo::quantize(v.latitude), geo::quantize(v.longitude))
+                .map_err(ErrorCode::from)?;
+        }
+        LootVoucherPayload::V2(v) => {
+            require!(v.player == *player, ErrorCode::VoucherWrongPlayer);
+            require!(
+                v.domain.program_id == crate::ID && v.domain.cluster == registry.cluster,
+                ErrorCode::VoucherWrongDomain
+            );
+            require!(now < v.expires_at, ErrorCode::VoucherExpired);
+            v.location.validate()?;
+            player_state.nonces.consume(v.nonce)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// CAPS owed for one claim, or 0 if it uses today's free allowance
+fn loot_fee_due(
+    config: &LootConfig,
+    player_state: &mut LootPlayer,
+    rarity: LootRarity,
+    now: i64,
+) -> Result<u64> {
+    let day = now.div_euclid(SECONDS_PER_DAY);
+    if player_state.free_claim_day != day {
+        player_state.free_claim_day = day;
+        player_state.free_claims_used = 0;
+    }
+    if player_state.free_claims_used < config.free_claims_per_day {
+        player_state.free_claims_used += 1;
+        return Ok(0);
+    }
+    config.claim_fee_for(rarity)
+}
+
+/// Message a witness signs: the voucher hash plus the witness's own timestamp
+fn witness_message(voucher_hash: &[u8; 32], timestamp: i64) -> Vec<u8> {
+    [b"fizzloot-witness".as_ref(), voucher_hash, &timestamp.to_le_bytes()].concat()
+}
+
+/// Require k-of-n fresh witness attestations when the cache's rarity needs them,
+/// counting only witnesses `trusted` accepts (see `witness_trusted`)
+fn verify_witnesses(
+    config: &LootConfig,
+    instructions_sysvar: &AccountInfo,
+    voucher: &LootVoucher,
+    attestations: &[WitnessAttestation],
+    trusted: impl Fn(&Pubkey) -> bool,
+    now: i64,
+) -> Result<()> {
+    if !config.needs_witnesses(voucher.payload.roll().rarity) {
+        return Ok(());
+    }
+
+    let voucher_hash = solana_sha256_hasher::hash(&voucher.message()?).to_bytes();
+    let signatures = ed25519::load_signatures(instructions_sysvar)?;
+    let mut witnesses: Vec<Pubkey> = Vec::with_capacity(attestations.len());
+    for attestation in attestations {
+        require!(!witnesses.contains(&attestation.witness), ErrorCode::DuplicateWitness);
+        require!(trusted(&attestation.witness), ErrorCode::UntrustedWitness);
+        require!(
+            now.abs_diff(attestation.timestamp) <= config.witness_max_age_seconds as u64,
+            ErrorCode::StaleAttestation
+        );
+        ed25519::require_signature(
+            &signatures,
+            &attestation.witness.to_bytes(),
+            &witness_message(&voucher_hash, attestation.timestamp),
+            &attestation.signature,
+        )?;
+        witnesses.push(attestation.witness);
+    }
+
+    require!(
+        witnesses.len() >= config.witnesses_required as usize,
+        ErrorCode::NotEnoughWitnesses
+    );
+    Ok(())
+}
+
+/// Beacons always count; a player counts only if the authority registered it
+/// with `register_witness`, `accept_player_witnesses` is on, and it is not the
+/// claimant or the voucher server
+fn witness_trusted(
+    config: &LootConfig,
+    beacons: &BeaconRegistry,
+    registered: &[Pubkey],
+    excluded: &[Pubkey],
+    witness: &Pubkey,
+) -> bool {
+    beacons.beacons.contains(witness)
+        || (config.accept_player_witnesses
+            && registered.contains(witness)
+            && !excluded.contains(witness))
+}
+
+/// Witness keys of the `LootWitness` accounts passed as remaining accounts.
+/// Only accounts this program owns deserialize, so a claimant can't forge one.
+fn registered_witnesses(accounts: &[AccountInfo]) -> Result<Vec<Pubkey>> {
+    let mut witnesses = Vec::with_capacity(accounts.len());
+    for account in accounts {
+        require_keys_eq!(*account.owner, crate::ID, ErrorCode::UntrustedWitness);
+        let data = account.try_borrow_data()?;
+        witnesses.push(LootWitness::try_deserialize(&mut &data[..])?.witness);
+    }
+    Ok(witnesses)
+}
+
+/// Count a claim against its geocell's per-epoch quota and the player's cooldown there
+fn record_cell_claim(
+    config: &LootConfig,
+    cell: &mut LocationCell,
+    cooldown: &mut CellCooldown,
+    now: i64,
+) -> Result<()> {
+    let epoch = now.div_euclid(config.cell_epoch_seconds);
+    if cell.epoch != epoch {
+        cell.epoch = epoch;
+        cell.claims = 0;
+    }
+    if config.max_claims_per_cell > 0 {
+        require!(cell.claims < config.max_claims_per_cell, ErrorCode::CellQuotaExceeded);
+    }
+    cell.claims += 1;
+
+    if cooldown.last_claim_at != 0 {
+        require!(
+            now >= cooldown.last_claim_at.saturating_add(config.cell_cooldown_seconds),
+            ErrorCode::CellCooldownActive
+        );
+    }
+    cooldown.last_claim_at = now;
+    Ok(())
+}
+
+/// Token accounts a claim fee moves between
+struct ClaimFeeAccounts<'info> {
+    token_program: AccountInfo<'info>,
+    caps_mint: AccountInfo<'info>,
+    player_caps_ata: AccountInfo<'info>,
+    treasury_caps_ata: AccountInfo<'info>,
+    player: AccountInfo<'info>,
+    sol_treasury: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+/// Collect a claim fee the way the player chose: CAPS burned/sent to the
+/// treasury (see `LootConfig::split_fee`), or the same CAPS amount converted
+/// to SOL and sent to the SOL treasury. Returns the CAPS burned.
+fn charge_claim_fee(
+    accounts: &ClaimFeeAccounts,
+    config: &LootConfig,
+    payment: LootPaymentMethod,
+    burned: u64,
+    to_treasury: u64,
+) -> Result<u64> {
+    if payment == LootPaymentMethod::Sol {
+        let caps_amount = burned.checked_add(to_treasury).ok_or(ErrorCode::FeeOverflow)?;
+        let lamports = config.caps_to_lamports(caps_amount)?;
+        if lamports > 0 {
+            anchor_lang::system_program::transfer(
+                CpiContext::new(
+                    accounts.system_program.clone(),
+                    anchor_lang::system_program::Transfer {
+                        from: accounts.player.clone(),
+                        to: accounts.sol_treasury.clone(),
+                    },
+                ),
+                lamports,
+            )?;
+        }
+        msg!("Claim fee paid in SOL: {} lamports", lamports);
+        return Ok(0);
+    }
+
+    if burned > 0 {
+        burn(
+            CpiContext::new(
+                accounts.token_program.clone(),
+                Burn {
+                    mint: accounts.caps_mint.clone(),
+                    from: accounts.player_caps_ata.clone(),
+                    authority: accounts.player.clone(),
+                },
+            ),
+            burned,
+        )?;
+    }
+    if to_treasury > 0 {
+        token::transfer(
+            CpiContext::new(
+                accounts.token_program.clone(),
+                Transfer {
+                    from: accounts.player_caps_ata.clone(),
+                    to: accounts.treasury_caps_ata.clone(),
+                    authority: accounts.player.clone(),
+                },
+            ),
+            to_treasury,
+        )?;
+    }
+
+    Ok(burned)
+}
+
+/// Cut `s` to at most `max` bytes without splitting a UTF-8 character
+fn truncate_utf8(mut s: String, max: usize) -> String {
+    if s.len() > max {
+        let end = (0..=max).rev().find(|&i| s.is_char_boundary(i)).unwrap_or(0);
+        s.truncate(end);
+    }
+    s
+}
+
+/// Fill a loot metadata template. Placeholders: {id}, {geohash}, {rarity},
+/// {lat}, {lon} (4-decimal degrees, "?" for private claims) and {hint}
+/// (voucher location hint). {hint} goes last so player-visible text is never
+/// expanded itself.
+fn render_loot_template(template: &str, payload: &LootVoucherPayload) -> String {
+    let mut out = template.replace("{id}", &payload.loot_id().to_string());
+    if out.contains("{geohash}") {
+        out = out.replace("{geohash}", &payload.geohash_str());
+    }
+    out = out.replace("{rarity}", payload.roll().rarity.label());
+    if out.contains("{lat}") || out.contains("{lon}") {
+        let (lat, lon) = match payload.coordinates() {
+            Some((lat_micro, lon_micro)) => (
+                geo::format_microdegrees(lat_micro),
+                geo::format_microdegrees(lon_micro),
+            ),
+            None => ("?".to_string(), "?".to_string()),
+        };
+        out = out.replace("{lat}", &lat).replace("{lon}", &lon);
+    }
+    out.replace("{hint}", payload.location_hint())
+}
+
+/// Render a template for a fused item: it has no location, so {geohash},
+/// {lat} and {lon} become "?" and {hint} becomes "Fused"
+fn render_fused_template(template: &str, loot_id: u64, rarity: LootRarity) -> String {
+    template
+        .replace("{id}", &loot_id.to_string())
+        .replace("{rarity}", rarity.label())
+        .replace("{geohash}", "?")
+        .replace("{lat}", "?")
+        .replace("{lon}", "?")
+        .replace("{hint}", "Fused")
+}
+
+/// Loot id of the item fused from `burned_ids` (ascending)
+fn fused_loot_id_for(burned_ids: &[u64]) -> u64 {
+    let ids: Vec<u8> = burned_ids.iter().flat_map(|id| id.to_le_bytes()).collect();
+    let hash = solana_sha256_hasher::hashv(&[b"fizzloot-fusion".as_ref(), &ids]).to_bytes();
+    let mut id_bytes = [0u8; 8];
+    id_bytes.copy_from_slice(&hash[..8]);
+    u64::from_le_bytes(id_bytes) | FUSED_LOOT_ID_FLAG
+}
+
+/// Hash of `slot` from SlotHashes sysvar data (u64 count, then (slot, hash)
+/// pairs), or None once it has aged out
+fn slot_hash_at(data: &[u8], slot: u64) -> Option<[u8; 32]> {
+    let count = u64::from_le_bytes(data.get(..8)?.try_into().ok()?);
+    data[8..]
+        .chunks_exact(8 + 32)
+        .take(count as usize)
+        .find(|entry| entry[..8] == slot.to_le_bytes())
+        .and_then(|entry| entry[8..].try_into().ok())
+}
+
+/// Seed for a fused output roll. Without the commit slot's hash it is all
+/// zeros: no stat boosts and, for mixed inputs, the first category.
+fn fusion_seed(fused_loot_id: u64, commit_slot_hash: Option<[u8; 32]>) -> [u8; 32] {
+    let Some(slot_hash) = commit_slot_hash else {
+        return [0; 32];
+    };
+    solana_sha256_hasher::hashv(&[
+        b"fizzloot-fusion-roll".as_ref(),
+        &fused_loot_id.to_le_bytes(),
+        &slot_hash,
+    ])
+    .to_bytes()
+}
+
+/// Output roll: category kept if every input shares it, else picked by `seed`;
+/// each stat is the best input's, boosted 0-25% by `seed`
+fn fuse_roll(inputs: &[LootRoll], rarity: LootRarity, seed: &[u8; 32]) -> LootRoll {
+    const CATEGORIES: [LootCategory; 6] = [
+        LootCategory::Junk,
+        LootCategory::Weapon,
+        LootCategory::Armor,
+        LootCategory::Aid,
+        LootCategory::Ammo,
+        LootCategory::Collectible,
+    ];
+    let category = if inputs.iter().all(|r| r.category == inputs[0].category) {
+        inputs[0].category
+    } else {
+        CATEGORIES[seed[0] as usize % CATEGORIES.len()]
+    };
+    let boost = |best: u64, byte: u8| best + best * (byte % 26) as u64 / 100;
+    let best = |stat: fn(&LootRoll) -> u64| inputs.iter().map(stat).max().unwrap_or(0);
+
+    LootRoll {
+        rarity,
+        category,
+        stats: LootStats {
+            damage: boost(best(|r| r.stats.damage as u64), seed[1]).min(u16::MAX as u64) as u16,
+            armor: boost(best(|r| r.stats.armor as u64), seed[2]).min(u16::MAX as u64) as u16,
+            weight: best(|r| r.stats.weight as u64) as u16,
+            caps_value: boost(best(|r| r.stats.caps_value as u64), seed[3]).min(u32::MAX as u64)
+                as u32,
+        },
+    }
+}
+
+/// Attributes plugin for a Core loot asset
+fn core_loot_attributes(roll: &LootRoll) -> Attributes {
+    let attribute = |key: &str, value: String| Attribute {
+        key: key.to_string(),
+        value,
+    };
+    Attributes {
+        attribute_list: vec![
+            attribute("rarity", roll.rarity.label().to_string()),
+            attribute("category", format!("{:?}", roll.category)),
+            attribute("damage", roll.stats.damage.to_string()),
+            attribute("armor", roll.stats.armor.to_string()),
+            attribute("weight", roll.stats.weight.to_string()),
+            attribute("caps_value", roll.stats.caps_value.to_string()),
+        ],
+    }
+}
+
+/// Attributes plugin for a Core loot asset, including its mutable item state
+fn core_item_attributes(roll: &LootRoll, state: &LootItemState) -> Attributes {
+    let mods = state.mods.iter().map(u16::to_string).collect::<Vec<_>>().join(",");
+    let mut attributes = core_loot_attributes(roll);
+    attributes.attribute_list.extend(
+        [
+            ("durability", format!("{}/{}", state.durability, state.max_durability)),
+            ("level", state.level.to_string()),
+            ("mods", mods),
+        ]
+        .map(|(key, value)| Attribute {
+            key: key.to_string(),
+            value,
+        }),
+    );
+    attributes
+}
+
+/// Accounts for rewriting a Core loot asset's attributes
+struct CoreItemAccounts<'info> {
+    loot_asset: Option<AccountInfo<'info>>,
+    mpl_core_program: Option<AccountInfo<'info>>,
+    payer: AccountInfo<'info>,
+    loot_mint_authority: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+}
+
+/// Rewrite a Core item's Attributes plugin from its state. SPL items have
+/// immutable metadata, so `LootItemState` is what front ends read for them.
+fn sync_core_item_attributes(
+    accounts: &CoreItemAccounts,
+    authority_bump: u8,
+    roll: &LootRoll,
+    state: &LootItemState,
+) -> Result<()> {
+    if !state.is_core_asset {
+        return Ok(());
+    }
+    let (Some(asset), Some(mpl_core_program)) = (&accounts.loot_asset, &accounts.mpl_core_program)
+    else {
+        return err!(ErrorCode::CoreAssetRequired);
+    };
+
+    UpdatePluginV1CpiBuilder::new(mpl_core_program)
+        .asset(asset)
+        .payer(&accounts.payer)
+        .authority(Some(&accounts.loot_mint_authority))
+        .system_program(&accounts.system_program)
+        .plugin(Plugin::Attributes(core_item_attributes(roll, state)))
+        .invoke_signed(&[&[LOOT_MINT_AUTHORITY_SEEDS, &[authority_bump]]])?;
+    Ok(())
+}
+
+/// Compressed loot leaf: commits to the id, owner, roll, name and URI
+fn loot_leaf(
+    loot_id: u64,
+    owner: &Pubkey,
+    roll: &LootRoll,
+    name: &str,
+    uri: &str,
+) -> Result<merkle::Node> {
+    use solana_sha256_hasher::{hash, hashv};
+    Ok(hashv(&[
+        b"fizzloot-leaf".as_ref(),
+        &loot_id.to_le_bytes(),
+        owner.as_ref(),
+        &roll.try_to_vec()?,
+        hash(name.as_bytes()).as_ref(),
+        hash(uri.as_bytes()).as_ref(),
+    ])
+    .to_bytes())
+}
+
+/// Accounts needed to mint one loot NFT (shared by single and batch claims)
+struct LootMintAccounts<'info> {
+    payer: AccountInfo<'info>,
+    player: AccountInfo<'info>,
+    loot_mint: AccountInfo<'info>,
+    player_loot_ata: AccountInfo<'info>,
+    loot_metadata: AccountInfo<'info>,
+    loot_mint_authority: AccountInfo<'info>,
+    loot_mint_authority_bump: u8,
+    collection_mint: AccountInfo<'info>,
+    collection_metadata: AccountInfo<'info>,
+    collection_master_edition: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    associated_token_program: AccountInfo<'info>,
+    instructions_sysvar: AccountInfo<'info>,
+    metadata_program: AccountInfo<'info>,
+}
+
+/// Mint 1 loot token to the player, create its metadata and verify it
+/// into the active FIZZLOOT collection
+fn mint_loot_nft(
+    accounts: &LootMintAccounts,
+    metadata_config: &LootMetadataConfig,
+    name: String,
+    uri: String,
+) -> Result<()> {
+    let signer_seeds: &[&[&[u8]]] =
+        &[&[LOOT_MINT_AUTHORITY_SEEDS, &[accounts.loot_mint_authority_bump]]];
+
+    anchor_spl::token::mint_to(
+        CpiContext::new_with_signer(
+            accounts.token_program.clone(),
+            MintTo {
+                mint: accounts.loot_mint.clone(),
+                to: accounts.player_loot_ata.clone(),
+                authority: accounts.loot_mint_authority.clone(),
+            },
+            signer_seeds,
+        ),
+        1,
+    )?;
+
+    CreateV1CpiBuilder::new(&accounts.metadata_program)
+        .metadata(&accounts.loot_metadata)
+        .mint(&accounts.loot_mint, true)
+        .authority(&accounts.loot_mint_authority)
+        .payer(&accounts.payer)
+        .update_authority(&accounts.loot_mint_authority, true)
+        .system_program(&accounts.system_program)
+        .sysvar_instructions(&accounts.instructions_sysvar)
+        .token_standard(TokenStandard::NonFungible)
+        .name(name)
+        .symbol(metadata_config.symbol.clone())
+        .uri(uri)
+        .seller_fee_basis_points(0)
+        .creators(vec![])
+        .collection(Collection {
+            verified: false,
+            key: accounts.collection_mint.key(),
+        })
+        .is_mutable(false)
+        .primary_sale_happened(true)
+        .invoke_signed(signer_seeds)?;
+
+    // loot_mint_authority is the collection's update authority
+    VerifyCollectionV1CpiBuilder::new(&accounts.metadata_program)
+        .authority(&accounts.loot_mint_authority)
+        .metadata(&accounts.loot_metadata)
+        .collection_mint(&accounts.collection_mint)
+        .collection_metadata(Some(&accounts.collection_metadata))
+        .collection_master_edition(Some(&accounts.collection_master_edition))
+        .system_program(&accounts.system_program)
+        .sysvar_instructions(&accounts.instructions_sysvar)
+        .invoke_signed(signer_seeds)?;
+
+    Ok(())
+}
+
+/// Create the loot mint PDA for `loot_id` and the player's ATA for it
+fn init_loot_mint_and_ata(accounts: &LootMintAccounts, loot_id: u64) -> Result<()> {
+    let loot_id_bytes = loot_id.to_le_bytes();
+    let (expected_mint, mint_bump) =
+        Pubkey::find_program_address(&[LOOT_MINT_AUTHORITY_SEEDS, &loot_id_bytes], &crate::ID);
+    require_keys_eq!(accounts.loot_mint.key(), expected_mint, ErrorCode::LootMintAddressMismatch);
+    require_keys_eq!(
+        accounts.player_loot_ata.key(),
+        anchor_spl::associated_token::get_associated_token_address(
+            &accounts.player.key(),
+            &expected_mint
+        ),
+        ErrorCode::LootAtaMismatch
+    );
+
+    create_pda(
+        &accounts.payer,
+        &accounts.system_program,
+        &accounts.loot_mint,
+        &[LOOT_MINT_AUTHORITY_SEEDS, &loot_id_bytes, &[mint_bump]],
+        Mint::LEN,
+        &token::ID,
+    )?;
+
+    token::initialize_mint2(
+        CpiContext::new(
+            accounts.token_program.clone(),
+            token::InitializeMint2 {
+                mint: accounts.loot_mint.clone(),
+            },
+        ),
+        0,
+        &accounts.loot_mint_authority.key(),
+        None,
+    )?;
+
+    anchor_spl::associated_token::create(CpiContext::new(
+        accounts.associated_token_program.clone(),
+        anchor_spl::associated_token::Create {
+            payer: accounts.payer.clone(),
+            associated_token: accounts.player_loot_ata.clone(),
+            authority: accounts.player.clone(),
+            mint: accounts.loot_mint.clone(),
+            system_program: accounts.system_program.clone(),
+            token_program: accounts.token_program.clone(),
+        },
+    ))
+}
+
+/// Allocate a PDA owned by `owner`, paid for by `payer`. Like Anchor `init`,
+/// an address someone already sent lamports to is topped up and claimed
+/// rather than failing.
+fn create_pda<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    target: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    space: usize,
+    owner: &Pubkey,
+) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(space);
+    let current = target.lamports();
+    if current == 0 {
+        return anchor_lang::system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program.clone(),
+                anchor_lang::system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: target.clone(),
+                },
+                &[signer_seeds],
+            ),
+            rent,
+            space as u64,
+            owner,
+        );
+    }
+
+    system_transfer(system_program, payer, target, rent.saturating_sub(current))?;
+    anchor_lang::system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Allocate {
+                account_to_allocate: target.clone(),
+            },
+            &[signer_seeds],
+        ),
+        space as u64,
+    )?;
+    anchor_lang::system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Assign {
+                account_to_assign: target.clone(),
+            },
+            &[signer_seeds],
+        ),
+        owner,
+    )
+}
+
+/// Bump of the LootItem PDA for `loot_id`, failing if any claim path
+/// (NFT, batch, compressed or Core) has ever reserved it. Salvage and fusion
+/// only mark the LootItem burned, so the reservation is permanent.
+fn check_loot_id_unclaimed(loot_item: &AccountInfo, loot_id: u64) -> Result<u8> {
+    let (expected, bump) =
+        Pubkey::find_program_address(&[LOOT_ITEM_SEEDS, &loot_id.to_le_bytes()], &crate::ID);
+    require_keys_eq!(loot_item.key(), expected, ErrorCode::LootItemAddressMismatch);
+    require!(
+        loot_item.data_is_empty() && loot_item.owner == &anchor_lang::system_program::ID,
+        ErrorCode::LootAlreadyClaimed
+    );
+    Ok(bump)
+}
+
+/// Create the LootItem PDA that reserves `item.loot_id`. Every claim path
+/// goes through here, so a voucher redeemed on one path can't be redeemed
+/// again on another.
+fn reserve_loot_item<'info>(
+    payer: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    loot_item: &AccountInfo<'info>,
+    mut item: LootItem,
+) -> Result<()> {
+    let bump = check_loot_id_unclaimed(loot_item, item.loot_id)?;
+    let loot_id_bytes = item.loot_id.to_le_bytes();
+    create_pda(
+        payer,
+        system_program,
+        loot_item,
+        &[LOOT_ITEM_SEEDS, &loot_id_bytes, &[bump]],
+        LOOT_ITEM_SPACE,
+        &crate::ID,
+    )?;
+
+    item.bump = bump;
+    item.try_serialize(&mut &mut loot_item.try_borrow_mut_data()?[..])
+}
+
+/// Geocell checks for a batch voucher, creating the LocationCell / CellCooldown
+/// PDAs on first use (claim_loot gets them from Anchor `init_if_needed`)
+fn record_cell_claim_by_hand<'info>(
+    accounts: &ClaimLootBatch<'info>,
+    location_cell: &AccountInfo<'info>,
+    cell_cooldown: &AccountInfo<'info>,
+    cell_key: [u8; geo::GEOHASH_LEN],
+    now: i64,
+) -> Result<()> {
+    let player = accounts.player.key();
+    let (expected_cell, cell_bump) =
+        Pubkey::find_program_address(&[LOCATION_CELL_SEEDS, &cell_key], &crate::ID);
+    require_keys_eq!(location_cell.key(), expected_cell, ErrorCode::BatchLocationCellMismatch);
+    let (expected_cooldown, cooldown_bump) = Pubkey::find_program_address(
+        &[CELL_COOLDOWN_SEEDS, player.as_ref(), &cell_key],
+        &crate::ID,
+    );
+    require_keys_eq!(cell_cooldown.key(), expected_cooldown, ErrorCode::BatchCellCooldownMismatch);
+
+    // Vouchers in the same cell share these accounts, so always re-read them
+    let mut cell = if location_cell.data_is_empty() {
+        create_pda(
+            &accounts.fee_payer.to_account_info(),
+            &accounts.system_program.to_account_info(),
+            location_cell,
+            &[LOCATION_CELL_SEEDS, &cell_key, &[cell_bump]],
+            LOCATION_CELL_SPACE,
+            &crate::ID,
+        )?;
+        LocationCell { prefix: cell_key, epoch: 0, claims: 0, bump: cell_bump }
+    } else {
+        LocationCell::try_deserialize(&mut &location_cell.try_borrow_data()?[..])?
+    };
+    let mut cooldown = if cell_cooldown.data_is_empty() {
+        create_pda(
+            &accounts.fee_payer.to_account_info(),
+            &accounts.system_program.to_account_info(),
+            cell_cooldown,
+            &[CELL_COOLDOWN_SEEDS, player.as_ref(), &cell_key, &[cooldown_bump]],
+            CELL_COOLDOWN_SPACE,
+            &crate::ID,
+        )?;
+        CellCooldown { last_claim_at: 0, bump: cooldown_bump }
+    } else {
+        CellCooldown::try_deserialize(&mut &cell_cooldown.try_borrow_data()?[..])?
+    };
+
+    record_cell_claim(&accounts.loot_config, &mut cell, &mut cooldown, now)?;
+
+    cell.try_serialize(&mut &mut location_cell.try_borrow_mut_data()?[..])?;
+    cooldown.try_serialize(&mut &mut cell_cooldown.try_borrow_mut_data()?[..])
+}
+
+// ============ DATA STRUCTURES ============
+
+/// Signed loot voucher: versioned payload + server signature over it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootVoucher {
+    pub payload: LootVoucherPayload,
+    pub server_signature: [u8; 64],
+}
+
+impl LootVoucher {
+    /// Bytes the server signs: the Borsh-encoded payload (version byte first)
+    pub fn message(&self) -> Result<Vec<u8>> {
+        Ok(self.payload.try_to_vec()?)
+    }
+}
+
+/// Voucher layouts. The Borsh enum tag is the version byte, so old and
+/// new layouts can be claimed side by side during a rollout.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum LootVoucherPayload {
+    V1(LootVoucherV1),
+    V2(LootVoucherV2),
+}
+
+impl LootVoucherPayload {
+    pub fn version(&self) -> u8 {
+        match self {
+            LootVoucherPayload::V1(_) => 1,
+            LootVoucherPayload::V2(_) => 2,
+        }
+    }
+
+    pub fn loot_id(&self) -> u64 {
+        match self {
+            LootVoucherPayload::V1(v) => v.loot_id,
+            LootVoucherPayload::V2(v) => v.loot_id,
+        }
+    }
+
+    /// (latitude, longitude) in microdegrees, `None` for private claims;
+    /// V1 floats are quantized
+    pub fn coordinates(&self) -> Option<(i32, i32)> {
+        match self {
+            LootVoucherPayload::V1(v) => {
+                Some((geo::quantize(v.latitude), geo::quantize(v.longitude)))
+            }
+            LootVoucherPayload::V2(v) => match v.location {
+                VoucherLocation::Exact { lat_micro, lon_micro, .. } => Some((lat_micro, lon_micro)),
+                VoucherLocation::Private { .. } => None,
+            },
+        }
+    }
+
+    /// Geohash cell (coarse and zero padded for private claims); V1 vouchers
+    /// don't carry one, so it is derived (zeros if out of range)
+    pub fn geohash(&self) -> [u8; geo::GEOHASH_LEN] {
+        match self {
+            LootVoucherPayload::V1(v) => {
+                geo::geohash_cell(geo::quantize(v.latitude), geo::quantize(v.longitude))
+                    .unwrap_or_default()
+            }
+            LootVoucherPayload::V2(v) => match v.location {
+                VoucherLocation::Exact { geohash, .. } | VoucherLocation::Private { geohash, .. } => {
+                    geohash
+                }
+            },
+        }
+    }
+
+    /// `geohash()` without its zero padding
+    pub fn geohash_str(&self) -> String {
+        let cell = self.geohash();
+        let len = cell.iter().position(|&c| c == 0).unwrap_or(cell.len());
+        String::from_utf8_lossy(&cell[..len]).into_owned()
+    }
+
+    /// Hash of the exact location for private claims
+    pub fn location_commitment(&self) -> Option<[u8; 32]> {
+        match self {
+            LootVoucherPayload::V2(LootVoucherV2 {
+                location: VoucherLocation::Private { commitment, .. },
+                ..
+            }) => Some(*commitment),
+            _ => None,
+        }
+    }
+
+    /// Where the claim happened, for logs: coordinates or the coarse cell
+    pub fn location_label(&self) -> String {
+        match self.coordinates() {
+            Some((lat_micro, lon_micro)) => format!(
+                "({}, {})",
+                geo::format_microdegrees(lat_micro),
+                geo::format_microdegrees(lon_micro)
+            ),
+            None => format!("cell {}", self.geohash_str()),
+        }
+    }
+
+    pub fn location_hint(&self) -> &str {
+        match self {
+            LootVoucherPayload::V1(v) => &v.location_hint,
+            LootVoucherPayload::V2(v) => &v.location_hint,
+        }
+    }
+
+    /// V1 vouchers predate rarity and always roll a plain Common cache
+    pub fn roll(&self) -> LootRoll {
+        match self {
+            LootVoucherPayload::V1(_) => LootRoll::default(),
+            LootVoucherPayload::V2(v) => v.roll,
+        }
+    }
+}
+
+/// Legacy layout: claimable by anyone, forever (gated by `accept_legacy_vouchers`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootVoucherV1 {
+    pub loot_id: u64,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub timestamp: i64,
+    pub location_hint: String,
+}
+
+/// Player-bound, expiring, single-use layout
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootVoucherV2 {
+    pub loot_id: u64,
+    pub player: Pubkey,
+    /// Exact or private (committed) location, chosen by the player per claim
+    pub location: VoucherLocation,
+    pub timestamp: i64,
+    pub expires_at: i64,
+    /// Single use per player; may arrive out of order within `NONCE_WINDOW`
+    pub nonce: u64,
+    pub domain: VoucherDomain,
+    /// Requested delivery; `LootConfig.claim_mode` may override it
+    pub claim_mode: LootClaimMode,
+    /// Rarity, category and stats rolled by backend/lib/lootTable.js
+    pub roll: LootRoll,
+    pub location_hint: String,
+}
+
+/// Where a V2 claim happened. Vouchers travel in public transaction data, so a
+/// private claim's voucher must not contain the exact spot at all.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum VoucherLocation {
+    /// Fixed-point coordinates (degrees * 1e6, see geo.rs) and their geohash,
+    /// checked on-chain
+    Exact {
+        lat_micro: i32,
+        lon_micro: i32,
+        geohash: [u8; geo::GEOHASH_LEN],
+    },
+    /// Only a coarse cell is public; `commitment` is `geo::location_commitment`
+    /// over the exact coordinates and a salt the backend gives the player
+    Private {
+        geohash: [u8; geo::GEOHASH_LEN],
+        commitment: [u8; 32],
+    },
+}
+
+impl VoucherLocation {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            VoucherLocation::Exact { lat_micro, lon_micro, geohash } => {
+                let cell = geo::geohash_cell(*lat_micro, *lon_micro).map_err(ErrorCode::from)?;
+                require!(cell == *geohash, ErrorCode::GeohashMismatch);
+            }
+            VoucherLocation::Private { geohash, .. } => {
+                require!(geo::is_coarse_cell(geohash), ErrorCode::PrivateCellTooPrecise);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rarity tiers (mirrors backend/lib/lootTable.js), ordered Common < Legendary
+#[derive(
+    AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Default,
+)]
+pub enum LootRarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl LootRarity {
+    pub const COUNT: usize = 5;
+
+    /// Claim fee multiplier in basis points of `LootConfig.claim_fee`
+    pub fn fee_multiplier_bps(&self) -> u64 {
+        match self {
+            LootRarity::Common => 10_000,
+            LootRarity::Uncommon => 15_000,
+            LootRarity::Rare => 25_000,
+            LootRarity::Epic => 50_000,
+            LootRarity::Legendary => 100_000,
+        }
+    }
+
+    /// `{rarity}` in metadata templates
+    pub fn label(&self) -> &'static str {
+        match self {
+            LootRarity::Common => "Common",
+            LootRarity::Uncommon => "Uncommon",
+            LootRarity::Rare => "Rare",
+            LootRarity::Epic => "Epic",
+            LootRarity::Legendary => "Legendary",
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum LootCategory {
+    #[default]
+    Junk,
+    Weapon,
+    Armor,
+    Aid,
+    Ammo,
+    Collectible,
+}
+
+/// Stats rolled server-side when the voucher is issued
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LootStats {
+    pub damage: u16,
+    pub armor: u16,
+    pub weight: u16,
+    pub caps_value: u32,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct LootRoll {
+    pub rarity: LootRarity,
+    pub category: LootCategory,
+    pub stats: LootStats,
+}
+
+impl LootRoll {
+    pub const SIZE: usize = 1 + 1 + 2 + 2 + 2 + 4;
+}
+
+/// On-chain record of a claimed loot NFT's roll
+#[account]
+pub struct LootItem {
+    pub loot_id: u64,
+    /// SPL mint, the asset address for Core claims, or the loot tree for
+    /// compressed claims
+    pub mint: Pubkey,
+    pub roll: LootRoll,
+    pub claimed_by: Pubkey,
+    pub claimed_at: i64,
+    /// Set for private claims, see `reveal_loot_location`
+    pub location_commitment: Option<[u8; 32]>,
+    /// Set once salvaged or fused. The record is never closed, so its loot
+    /// id stays reserved on every claim path.
+    pub burned: bool,
+    pub bump: u8,
+}
+
+impl LootItem {
+    /// Mark the item destroyed, leaving the account as a tombstone
+    pub fn burn(&mut self) -> Result<()> {
+        require!(!self.burned, ErrorCode::LootItemBurned);
+        self.burned = true;
+        Ok(())
+    }
+}
+
+/// Mutable side of a loot item, one per loot id (not per `LootItem.mint`,
+/// which every compressed item in a tree shares).
+/// Changed only by server wear vouchers and `repair_item`.
+#[account]
+pub struct LootItemState {
+    pub mint: Pubkey,
+    pub loot_id: u64,
+    pub durability: u16,
+    pub max_durability: u16,
+    pub level: u8,
+    /// Installed modification ids (backend lootTable.js)
+    pub mods: Vec<u16>,
+    /// Last applied `LootWearPayload.nonce`
+    pub wear_nonce: u64,
+    /// Core assets mirror this state in their Attributes plugin
+    pub is_core_asset: bool,
+    pub bump: u8,
+}
+
+/// Server-signed item update, verified like a loot voucher
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootWearVoucher {
+    pub payload: LootWearPayload,
+    pub server_signature: [u8; 64],
+}
+
+impl LootWearVoucher {
+    /// Bytes the server signs: the Borsh-encoded payload
+    pub fn message(&self) -> Result<Vec<u8>> {
+        Ok(self.payload.try_to_vec()?)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LootWearPayload {
+    pub mint: Pubkey,
+    /// Tells apart compressed items, which share their tree as `mint`
+    pub loot_id: u64,
+    /// Must exceed the item's last applied nonce
+    pub nonce: u64,
+    pub durability_loss: u16,
+    pub levels_gained: u8,
+    pub new_mods: Vec<u16>,
+    pub expires_at: i64,
+    pub domain: VoucherDomain,
+}
+
+/// Stops a voucher signed for one deployment being replayed on another
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct VoucherDomain {
+    pub program_id: Pubkey,
+    pub cluster: LootCluster,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LootCluster {
+    Localnet,
+    Devnet,
+    Testnet,
+    Mainnet,
+}
+
+/// FIZZLOOT collection (season 0) or seasonal sub-collection
+#[account]
+pub struct LootCollection {
+    pub mint: Pubkey,
+    pub season: u16,
+    /// Root collection mint for seasonal sub-collections
+    pub parent: Option<Pubkey>,
+    /// Claims may only mint into active collections
+    pub active: bool,
+    pub bump: u8,
+}
+
+/// How a cache is delivered
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LootClaimMode {
+    /// SPL mint + metadata (claim_loot / claim_loot_batch)
+    Nft,
+    /// Leaf in a program-owned merkle tree (claim_loot_compressed)
+    Compressed,
+    /// Metaplex Core asset with an Attributes plugin (claim_loot_core)
+    Core,
+}
+
+/// Currency a player pays a claim fee in
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LootPaymentMethod {
+    /// Burn/treasury split of `LootConfig.claim_fee`
+    Caps,
+    /// Same fee converted at `LootConfig.lamports_per_caps`
+    Sol,
+}
+
+/// Config-level override of the voucher's claim mode
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LootClaimModePolicy {
+    VoucherChooses,
+    NftOnly,
+    CompressedOnly,
+    CoreOnly,
+}
+
+/// Authority-set loot claim settings
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct LootConfigParams {
+    pub claim_mode: LootClaimModePolicy,
+    /// CAPS charged for a Common claim (scaled up by rarity)
+    pub claim_fee: u64,
+    /// Share of the fee burned; the rest goes to `treasury`
+    pub burn_bps: u16,
+    /// CAPS token account receiving the non-burned share
+    pub treasury: Pubkey,
+    /// Fee-free claims per wallet per UTC day
+    pub free_claims_per_day: u8,
+    /// Geohash prefix length defining a LocationCell (1..=8)
+    pub cell_precision: u8,
+    /// Length of a LocationCell quota epoch
+    pub cell_epoch_seconds: i64,
+    /// Claims allowed per cell per epoch (0 = unlimited)
+    pub max_claims_per_cell: u32,
+    /// Minimum time between one player's claims in the same cell
+    pub cell_cooldown_seconds: i64,
+    /// CAPS sent to the treasury per claim whose rent a relayer paid (0 = free)
+    pub sponsored_rent_caps: u64,
+    /// System account receiving fees paid in SOL
+    pub sol_treasury: Pubkey,
+    /// Lamports charged per whole CAPS of fee when paying in SOL (0 = CAPS only)
+    pub lamports_per_caps: u64,
+    /// Caches of this rarity or better need witness attestations
+    pub witness_min_rarity: LootRarity,
+    /// Distinct attestations required (0 = off)
+    pub witnesses_required: u8,
+    /// Max age (either direction) of an attestation's timestamp
+    pub witness_max_age_seconds: i64,
+    /// Also count player wallets registered with `register_witness`. Off by
+    /// default; unregistered player keys never count, since a claimant could
+    /// generate and sign with as many as it likes.
+    pub accept_player_witnesses: bool,
+    /// Durability new `LootItemState`s start at
+    pub item_max_durability: u16,
+    /// CAPS burned per durability point restored by `repair_item`
+    pub repair_caps_per_point: u64,
+}
+
+/// Loot claim settings, managed by `FizzConfig.authority`
+#[account]
+pub struct LootConfig {
+    pub claim_mode: LootClaimModePolicy,
+    pub claim_fee: u64,
+    pub burn_bps: u16,
+    pub treasury: Pubkey,
+    pub free_claims_per_day: u8,
+    pub cell_precision: u8,
+    pub cell_epoch_seconds: i64,
+    pub max_claims_per_cell: u32,
+    pub cell_cooldown_seconds: i64,
+    pub sponsored_rent_caps: u64,
+    pub sol_treasury: Pubkey,
+    pub lamports_per_caps: u64,
+    pub witness_min_rarity: LootRarity,
+    pub witnesses_required: u8,
+    pub witness_max_age_seconds: i64,
+    pub accept_player_witnesses: bool,
+    pub item_max_durability: u16,
+    pub repair_caps_per_point: u64,
+    pub bump: u8,
+}
+
+impl LootConfig {
+    pub fn apply(&mut self, params: &LootConfigParams) -> Result<()> {
+        require!(params.burn_bps <= 10_000, ErrorCode::InvalidBurnBps);
+        require!(
+            (1..=geo::GEOHASH_LEN as u8).contains(&params.cell_precision),
+            ErrorCode::InvalidCellPrecision
+        );
+        require!(params.cell_epoch_seconds > 0, ErrorCode::InvalidCellEpoch);
+        require!(params.cell_cooldown_seconds >= 0, ErrorCode::InvalidCellEpoch);
+        require!(
+            params.witnesses_required == 0 || params.witness_max_age_seconds > 0,
+            ErrorCode::InvalidWitnessConfig
+        );
+        require!(params.item_max_durability > 0, ErrorCode::InvalidItemDurability);
+        self.claim_mode = params.claim_mode;
+        self.claim_fee = params.claim_fee;
+        self.burn_bps = params.burn_bps;
+        self.treasury = params.treasury;
+        self.free_claims_per_day = params.free_claims_per_day;
+        self.cell_precision = params.cell_precision;
+        self.cell_epoch_seconds = params.cell_epoch_seconds;
+        self.max_claims_per_cell = params.max_claims_per_cell;
+        self.cell_cooldown_seconds = params.cell_cooldown_seconds;
+        self.sponsored_rent_caps = params.sponsored_rent_caps;
+        self.sol_treasury = params.sol_treasury;
+        self.lamports_per_caps = params.lamports_per_caps;
+        self.witness_min_rarity = params.witness_min_rarity;
+        self.witnesses_required = params.witnesses_required;
+        self.witness_max_age_seconds = params.witness_max_age_seconds;
+        self.accept_player_witnesses = params.accept_player_witnesses;
+        self.item_max_durability = params.item_max_durability;
+        self.repair_caps_per_point = params.repair_caps_per_point;
+        Ok(())
+    }
+
+    /// LocationCell seed: the voucher's geohash cut to `cell_precision`, zero padded
+    /// (private claims only publish a coarse cell, so they share that coarser cell)
+    pub fn cell_key(&self, payload: &LootVoucherPayload) -> [u8; geo::GEOHASH_LEN] {
+        let len = (self.cell_precision as usize).min(geo::GEOHASH_LEN);
+        let mut key = [0u8; geo::GEOHASH_LEN];
+        key[..len].copy_from_slice(&payload.geohash()[..len]);
+        key
+    }
+
+    /// CAPS the player owes the treasury when `fee_payer` sponsored rent for `claims` claims
+    pub fn rent_reimbursement(&self, fee_payer: &Pubkey, player: &Pubkey, claims: u64) -> Result<u64> {
+        if fee_payer == player {
+            return Ok(0);
+        }
+        self.sponsored_rent_caps
+            .checked_mul(claims)
+            .ok_or_else(|| error!(ErrorCode::FeeOverflow))
+    }
+
+    /// True if claiming a cache of this rarity needs witness attestations
+    pub fn needs_witnesses(&self, rarity: LootRarity) -> bool {
+        self.witnesses_required > 0 && rarity >= self.witness_min_rarity
+    }
+
+    /// SOL price of a CAPS amount, rounded up in the treasury's favor
+    pub fn caps_to_lamports(&self, caps_amount: u64) -> Result<u64> {
+        require!(self.lamports_per_caps > 0, ErrorCode::SolPaymentDisabled);
+        let lamports = (caps_amount as u128 * self.lamports_per_caps as u128)
+            .div_ceil(CAPS_DECIMALS as u128);
+        u64::try_from(lamports).map_err(|_| error!(ErrorCode::FeeOverflow))
+    }
+
+    /// Split a fee into (burned, sent to treasury) by `burn_bps`
+    pub fn split_fee(&self, amount: u64) -> (u64, u64) {
+        let burned = (amount as u128 * self.burn_bps as u128 / 10_000) as u64;
+        (burned, amount - burned)
+    }
+
+    /// CAPS charged to claim a cache of this rarity
+    pub fn claim_fee_for(&self, rarity: LootRarity) -> Result<u64> {
+        self.claim_fee
+            .checked_mul(rarity.fee_multiplier_bps())
+            .map(|fee| fee / 10_000)
+            .ok_or_else(|| error!(ErrorCode::FeeOverflow))
+    }
+
+    /// Claim path this voucher must use. V1 vouchers have no nonce, so they
+    /// are NFT-only whatever the policy: a policy that forces another path
+    /// rejects them rather than reroute them.
+    pub fn resolve_claim_mode(&self, payload: &LootVoucherPayload) -> Result<LootClaimMode> {
+        let LootVoucherPayload::V2(v) = payload else {
+            require!(
+                matches!(
+                    self.claim_mode,
+                    LootClaimModePolicy::VoucherChooses | LootClaimModePolicy::NftOnly
+                ),
+                ErrorCode::LegacyVoucherNftOnly
+            );
+            return Ok(LootClaimMode::Nft);
+        };
+        Ok(match self.claim_mode {
+            LootClaimModePolicy::VoucherChooses => v.claim_mode,
+            LootClaimModePolicy::NftOnly => LootClaimMode::Nft,
+            LootClaimModePolicy::CompressedOnly => LootClaimMode::Compressed,
+            LootClaimModePolicy::CoreOnly => LootClaimMode::Core,
+        })
+    }
+}
+
+/// Authority-set loot metadata templates (see `render_loot_template`)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, Debug)]
+pub struct LootMetadataParams {
+    /// e.g. "{rarity} Fizz Cache #{id}"
+    pub name_template: String,
+    /// Used instead of `name_template` for private claims, e.g. "{rarity} Cache near {geohash}"
+    pub private_name_template: String,
+    /// e.g. "FIZZLOOT"
+    pub symbol: String,
+    /// e.g. "https://atomicfizzcaps.xyz/loot/{id}.json"
+    pub uri_template: String,
+}
+
+/// Per-deployment loot metadata, managed by `FizzConfig.authority`
+#[account]
+pub struct LootMetadataConfig {
+    pub name_template: String,
+    pub private_name_template: String,
+    pub symbol: String,
+    pub uri_template: String,
+    pub bump: u8,
+}
+
+impl LootMetadataConfig {
+    pub fn apply(&mut self, params: &LootMetadataParams) -> Result<()> {
+        require!(
+            params.name_template.len() <= MAX_NAME_TEMPLATE_LEN
+                && params.private_name_template.len() <= MAX_NAME_TEMPLATE_LEN,
+            ErrorCode::NameTemplateTooLong
+        );
+        require!(params.symbol.len() <= 10, FizzError::SymbolTooLong);
+        require!(params.uri_template.len() <= 200, FizzError::UriTooLong);
+        self.name_template = params.name_template.clone();
+        self.private_name_template = params.private_name_template.clone();
+        self.symbol = params.symbol.clone();
+        self.uri_template = params.uri_template.clone();
+        Ok(())
+    }
+
+    /// Rendered name, cut to `MAX_LOOT_NAME_LEN` bytes
+    pub fn render_name(&self, payload: &LootVoucherPayload) -> String {
+        let template = if payload.location_commitment().is_some() {
+            &self.private_name_template
+        } else {
+            &self.name_template
+        };
+        truncate_utf8(render_loot_template(template, payload), MAX_LOOT_NAME_LEN)
+    }
+
+    /// Rendered URI, cut to `MAX_LOOT_URI_LEN` bytes
+    pub fn render_uri(&self, payload: &LootVoucherPayload) -> String {
+        truncate_utf8(render_loot_template(&self.uri_template, payload), MAX_LOOT_URI_LEN)
+    }
+
+    /// Name for a fused item; fusion has no location, so the private template is used
+    pub fn render_fused_name(&self, loot_id: u64, rarity: LootRarity) -> String {
+        truncate_utf8(
+            render_fused_template(&self.private_name_template, loot_id, rarity),
+            MAX_LOOT_NAME_LEN,
+        )
+    }
+
+    pub fn render_fused_uri(&self, loot_id: u64, rarity: LootRarity) -> String {
+        truncate_utf8(render_fused_template(&self.uri_template, loot_id, rarity), MAX_LOOT_URI_LEN)
+    }
+}
+
+/// Program-owned compressed loot tree
+#[account]
+pub struct LootTree {
+    pub tree_id: u32,
+    pub tree: merkle::MerkleTree,
+    pub bump: u8,
+}
+
+/// Claims counted against one geohash cell in the current epoch
+#[account]
+pub struct LocationCell {
+    pub prefix: [u8; geo::GEOHASH_LEN],
+    /// `unix_timestamp / cell_epoch_seconds` that `claims` belongs to
+    pub epoch: i64,
+    pub claims: u32,
+    pub bump: u8,
+}
+
+/// A player's last claim in one geohash cell
+#[account]
+pub struct CellCooldown {
+    pub last_claim_at: i64,
+    pub bump: u8,
+}
+
+/// Per-player loot claim state (replay protection)
+#[account]
+pub struct LootPlayer {
+    pub player: Pubkey,
+    pub nonces: NonceWindow,
+    /// UTC day index (`unix_timestamp / 86400`) of `free_claims_used`
+    pub free_claim_day: i64,
+    pub free_claims_used: u8,
+    pub bump: u8,
+}
+
+/// Used V2 voucher nonces: the newest nonce redeemed plus a bitmap of the
+/// `NONCE_WINDOW` nonces below it, so vouchers can be redeemed in any order
+/// as long as they are no more than `NONCE_WINDOW` behind the newest.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct NonceWindow {
+    pub highest: u64,
+    /// Bit `i` is set once nonce `highest - i` has been redeemed
+    pub used: [u64; 4],
+}
+
+impl NonceWindow {
+    pub const SPACE: usize = 8 + 8 * 4;
+
+    fn is_used(&self, offset: u64) -> bool {
+        self.used[(offset / 64) as usize] & (1 << (offset % 64)) != 0
+    }
+
+    fn mark_used(&mut self, offset: u64) {
+        self.used[(offset / 64) as usize] |= 1 << (offset % 64);
+    }
+
+    /// Slide the window up so `highest + by` becomes bit 0
+    fn advance(&mut self, by: u64) {
+        if by >= NONCE_WINDOW {
+            self.used = [0; 4];
+            return;
+        }
+        let (words, bits) = ((by / 64) as usize, (by % 64) as u32);
+        let mut used = [0u64; 4];
+        for (i, word) in used.iter_mut().enumerate().skip(words) {
+            let src = i - words;
+            *word = self.used[src] << bits;
+            if bits > 0 && src > 0 {
+                *word |= self.used[src - 1] >> (64 - bits);
+            }
+        }
+        self.used = used;
+    }
+
+    /// Record `nonce` as redeemed. Fails if it was already used or is too
+    /// far behind the newest nonce to tell. Nonce 0 is never valid.
+    pub fn consume(&mut self, nonce: u64) -> Result<()> {
+        require!(nonce > 0, ErrorCode::VoucherNonceUsed);
+        if nonce > self.highest {
+            self.advance(nonce - self.highest);
+            self.highest = nonce;
+            self.mark_used(0);
+            return Ok(());
+        }
+        let offset = self.highest - nonce;
+        require!(offset < NONCE_WINDOW, ErrorCode::VoucherNonceTooOld);
+        require!(!self.is_used(offset), ErrorCode::VoucherNonceUsed);
+        self.mark_used(offset);
+        Ok(())
+    }
+}
+
+/// Registered voucher-signing key (server or KMS key)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct VoucherSigner {
+    pub key: Pubkey,
+    pub valid_from: i64,
+    pub valid_until: i64,
+    pub revoked: bool,
+}
+
+/// A beacon's or nearby player's signature that the claimant was at the cache.
+/// The signed message is `witness_message(sha256(voucher message), timestamp)`,
+/// verified through the Ed25519 precompile like the voucher itself.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct WitnessAttestation {
+    pub witness: Pubkey,
+    pub timestamp: i64,
+    pub signature: [u8; 64],
+}
+
+/// How many items of one rarity fuse into which rarity
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FusionRecipe {
+    /// Items burned per fusion (0 = this rarity can't be fused)
+    pub inputs: u8,
+    pub output: LootRarity,
+}
+
+/// Inputs burned by `fuse_loot`, waiting for `reveal_fusion` to roll and
+/// mint the output
+#[account]
+pub struct PendingFusion {
+    pub player: Pubkey,
+    pub fused_loot_id: u64,
+    pub burned_ids: Vec<u64>,
+    pub input_rarity: LootRarity,
+    pub output: LootRarity,
+    pub rolls: Vec<LootRoll>,
+    /// Slot of the `fuse_loot`; its hash seeds the output roll
+    pub commit_slot: u64,
+    pub bump: u8,
+}
+
+/// Fusion recipes indexed by input rarity, managed by `FizzConfig.authority`
+#[account]
+pub struct FusionRecipes {
+    pub recipes: [FusionRecipe; LootRarity::COUNT],
+    pub bump: u8,
+}
+
+impl FusionRecipes {
+    pub fn apply(&mut self, recipes: [FusionRecipe; LootRarity::COUNT]) -> Result<()> {
+        for (input, recipe) in recipes.iter().enumerate() {
+            require!(
+                recipe.inputs == 0
+                    || ((2..=MAX_FUSION_INPUTS).contains(&recipe.inputs)
+                        && recipe.output as usize > input),
+                ErrorCode::InvalidFusionRecipe
+            );
+        }
+        self.recipes = recipes;
+        Ok(())
+    }
+
+    pub fn recipe_for(&self, rarity: LootRarity) -> FusionRecipe {
+        self.recipes[rarity as usize]
+    }
+}
+
+/// Location beacon keys, managed by `FizzConfig.authority`
+#[account]
+pub struct BeaconRegistry {
+    pub beacons: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+/// A player wallet the authority vetted as a witness. Claims pass these as
+/// remaining accounts; they count only while `accept_player_witnesses` is on.
+#[account]
+pub struct LootWitness {
+    pub witness: Pubkey,
+    pub bump: u8,
+}
+
+/// What salvaging one loot NFT of a given rarity pays out
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct SalvagePayout {
+    /// CAPS sent from the salvage pool
+    pub caps: u64,
+    /// Crafting-material tokens minted
+    pub materials: u64,
+}
+
+/// Salvage payouts by rarity, managed by `FizzConfig.authority`
+#[account]
+pub struct SalvageTable {
+    pub payouts: [SalvagePayout; LootRarity::COUNT],
+    pub bump: u8,
+    pub pool_bump: u8,
+}
+
+impl SalvageTable {
+    pub fn payout_for(&self, rarity: LootRarity) -> SalvagePayout {
+        self.payouts[rarity as usize]
+    }
+}
+
+/// Keys allowed to sign loot vouchers, managed by `FizzConfig.authority`
+#[account]
+pub struct SignerRegistry {
+    pub signers: Vec<VoucherSigner>,
+    pub cluster: LootCluster,
+    pub accept_legacy_vouchers: bool,
+    pub bump: u8,
+}
+
+impl SignerRegistry {
+    /// Fails unless `key` is registered, not revoked and valid at `now`
+    pub fn check_signer(&self, key: &Pubkey, now: i64) -> Result<()> {
+        let signer = self
+            .signers
+            .iter()
+            .find(|s| s.key == *key)
+            .ok_or(ErrorCode::UnregisteredSigner)?;
+        require!(!signer.revoked, ErrorCode::SignerRevoked);
+        require!(
+            now >= signer.valid_from && now < signer.valid_until,
+            ErrorCode::SignerExpired
+        );
+        Ok(())
+    }
+}
+
+/// Launch type for transparency (stored on-chain)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FizzLaunchType {
+    CapsStandard,  // Regular launch (launch tier fee burned)
+    CapsVeteran,   // Veteran launch (discounted tier fee burned)
+    AdminUSDC,     // Admin bootstrap (USDC paid off-chain)
+    AdminFree,     // Official tokens
+}
+
+/// Fizz.fun global config. Fizz.fun has not been deployed yet, so this and
+/// `FizzBondingCurve` change layout in place; once live, a layout change needs
+/// a realloc migration instead.
+#[account]
+pub struct FizzConfig {
+    pub authority: Pubkey,
+    pub treasury: Pubkey,
+    pub caps_mint: Pubkey,
+    pub total_tokens_launched: u64,
+    pub total_volume_sol: u64,
+    pub total_caps_burned: u64,
+    pub admin_usdc_launches: u64,
+    pub params: FizzLaunchParams,
+    pub launch_tiers: Vec<FizzLaunchTier>,
+    pub bump: u8,
+}
+
+impl FizzConfig {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8
+        + FizzLaunchParams::SIZE
+        + 4 + MAX_LAUNCH_TIERS * FizzLaunchTier::SIZE
+        + 1;
+
+    /// Highest tier whose `min_caps` the balance meets
+    pub fn launch_tier_for(&self, caps_balance: u64) -> Option<FizzLaunchTier> {
+        self.launch_tiers
+            .iter()
+            .rev()
+            .find(|tier| caps_balance >= tier.min_caps)
+            .copied()
+    }
+}
+
+/// One row of the CAPS launch tier table
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FizzLaunchTier {
+    /// `CapsStandard` or `CapsVeteran`, recorded on the curve
+    pub launch_type: FizzLaunchType,
+    /// CAPS the creator must hold
+    pub min_caps: u64,
+    /// CAPS burned at launch
+    pub launch_fee: u64,
+    /// Trading fee on this tier's curves
+    pub fee_bps: u64,
+    /// Creator bonus at graduation, bps of SOL raised
+    pub creator_bonus_bps: u64,
+}
+
+impl FizzLaunchTier {
+    pub const SIZE: usize = 1 + 8 + 8 + 8 + 8;
+
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self {
+                launch_type: FizzLaunchType::CapsStandard,
+                min_caps: CAPS_TO_LAUNCH,
+                launch_fee: CAPS_LAUNCH_FEE,
+                fee_bps: FEE_BPS,
+                creator_bonus_bps: CREATOR_BONUS_BPS,
+            },
+            Self {
+                launch_type: FizzLaunchType::CapsVeteran,
+                min_caps: CAPS_VETERAN_THRESHOLD,
+                launch_fee: CAPS_VETERAN_FEE,
+                fee_bps: FEE_BPS,
+                creator_bonus_bps: CREATOR_BONUS_BPS,
+            },
+        ]
+    }
+
+    pub fn validate_table(tiers: &[Self]) -> Result<()> {
+        require!(
+            !tiers.is_empty() && tiers.len() <= MAX_LAUNCH_TIERS,
+            FizzError::InvalidLaunchTiers
+        );
+        for (i, tier) in tiers.iter().enumerate() {
+            require!(
+                matches!(
+                    tier.launch_type,
+                    FizzLaunchType::CapsStandard | FizzLaunchType::CapsVeteran
+                ),
+                FizzError::InvalidLaunchType
+            );
+            require!(
+                tier.launch_fee <= tier.min_caps
+                    && tier.fee_bps <= MAX_FEE_BPS
+                    && tier.creator_bonus_bps <= MAX_CREATOR_BONUS_BPS,
+                FizzError::InvalidLaunchTiers
+            );
+            if i > 0 {
+                require!(tier.min_caps > tiers[i - 1].min_caps, FizzError::InvalidLaunchTiers);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Authority-tunable launchpad economics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct FizzLaunchParams {
+    /// Trading fee on admin-launched curves only; CAPS launches take
+    /// `FizzLaunchTier.fee_bps` from the tier table
+    pub admin_fee_bps: u64,
+    /// Creator bonus on admin-launched curves only, bps of SOL raised; CAPS
+    /// launches take `FizzLaunchTier.creator_bonus_bps`
+    pub admin_creator_bonus_bps: u64,
+    /// Real SOL a curve must raise to graduate
+    pub graduation_sol: u64,
+    /// Virtual SOL that sets a new curve's starting price
+    pub virtual_sol: u64,
+    /// Swap fee in graduated pools
+    pub pool_fee_bps: u64,
+    /// Share of the pool fee (bps of the fee) sent to the treasury
+    pub pool_treasury_share_bps: u64,
+    /// Share of the pool fee (bps of the fee) sent to the CAPS buyback bucket
+    pub pool_buyback_share_bps: u64,
+}
+
+impl FizzLaunchParams {
+    pub const SIZE: usize = 7 * 8;
+    pub const MAX_CURVE_SUPPLY: u64 =
+        TOTAL_SUPPLY - TOTAL_SUPPLY / curve_math::BPS_DENOMINATOR * MIN_POOL_SUPPLY_BPS;
+
+    pub const DEFAULT: Self = Self {
+        admin_fee_bps: FEE_BPS,
+        admin_creator_bonus_bps: CREATOR_BONUS_BPS,
+        graduation_sol: GRADUATION_SOL,
+        virtual_sol: VIRTUAL_SOL,
+        pool_fee_bps: POOL_FEE_BPS,
+        pool_treasury_share_bps: POOL_TREASURY_SHARE_BPS,
+        pool_buyback_share_bps: POOL_BUYBACK_SHARE_BPS,
+    };
+
+    pub fn validate(&self) -> Result<()> {
+        require!(self.admin_fee_bps <= MAX_FEE_BPS, FizzError::InvalidLaunchParams);
+        require!(
+            self.admin_creator_bonus_bps <= MAX_CREATOR_BONUS_BPS,
+            FizzError::InvalidLaunchParams
+        );
+        require!(self.graduation_sol > 0, FizzError::InvalidLaunchParams);
+        require!(self.virtual_sol > 0, FizzError::InvalidLaunchParams);
+        // A bigger bonus leaves less SOL in the pool, so the curve sells more
+        self.curve_supply(MAX_CREATOR_BONUS_BPS)?;
+        require!(self.pool_fee_bps <= MAX_FEE_BPS, FizzError::InvalidLaunchParams);
+        require!(
+            self.pool_treasury_share_bps + self.pool_buyback_share_bps
+                <= curve_math::BPS_DENOMINATOR,
+            FizzError::InvalidLaunchParams
+        );
+        Ok(())
+    }
+
+    /// Tokens (of TOTAL_SUPPLY) a curve with this creator bonus sells, sized
+    /// so its graduated pool opens at the curve's closing price. The rest, at
+    /// least `MIN_POOL_SUPPLY_BPS`, seeds the pool.
+    pub fn curve_supply(&self, creator_bonus_bps: u64) -> Result<u64> {
+        let supply = curve_math::graduation_curve_supply(
+            TOTAL_SUPPLY,
+            self.virtual_sol,
+            self.graduation_sol,
+            creator_bonus_bps,
+        )
+        .map_err(|_| FizzError::InvalidLaunchParams)?;
+        require!(supply <= Self::MAX_CURVE_SUPPLY, FizzError::InvalidLaunchParams);
+        Ok(supply)
+    }
+}
+
+/// Constant-product pool a graduated token trades in. Its SOL side is held
+/// as lamports on this account (above rent); `sol_reserve` tracks it.
+#[account]
+pub struct FizzPool {
+    pub token_mint: Pubkey,
+    pub lp_mint: Pubkey,
+    pub token_vault: Pubkey,
+    pub sol_reserve: u64,
+    pub token_reserve: u64,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl FizzPool {
+    pub const SPACE: usize = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Inputs for `curve_math` (a pool has no virtual SOL)
+    pub fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            sol_reserve: self.sol_reserve,
+            virtual_sol: 0,
+            token_reserve: self.token_reserve,
+        }
+    }
+}
+
+/// SOL set aside from pool fees for CAPS buybacks
+#[account]
+pub struct FizzBuyback {
+    /// Lamports received over all time
+    pub total_accrued: u64,
+    pub bump: u8,
+}
+
+/// Side of a pool swap
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FizzSwapDirection {
+    /// SOL in, tokens out
+    Buy,
+    /// Tokens in, SOL out
+    Sell,
+}
+
+/// Admin record
+#[account]
+pub struct FizzAdminRecord {
+    pub admin: Pubkey,
+    pub added_at: i64,
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+/// Bonding curve state - OPTIMIZED for minimal rent (~0.002 SOL)
+/// Name, symbol, URI stored off-chain (indexed from events)
+#[account]
+pub struct FizzBondingCurve {
+    pub creator: Pubkey,      // 32 bytes
+    pub token_mint: Pubkey,   // 32 bytes
+    pub sol_reserve: u64,     // 8 bytes
+    pub token_reserve: u64,   // 8 bytes
+    pub graduated: bool,      // 1 byte
+    pub created_at: i64,      // 8 bytes
+    pub launch_type: FizzLaunchType, // 1 byte
+    // Snapshot of FizzConfig.params at creation, so retuning never moves live curves
+    pub virtual_sol: u64,     // 8 bytes
+    pub fee_bps: u64,         // 8 bytes
+    pub graduation_sol: u64,  // 8 bytes
+    pub creator_bonus_bps: u64, // 8 bytes
+    pub bump: u8,             // 1 byte
+    // TOTAL: 8 (discriminator) + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 4 * 8 + 1 = 131 bytes
+    // Rent: ~0.0018 SOL
+}
+
+impl FizzBondingCurve {
+    pub const SPACE: usize = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 4 * 8 + 1;
+
+    /// Copy the economics that must stay fixed for this curve's lifetime.
+    /// Fees start at the admin defaults; `apply_tier` replaces them for CAPS launches.
+    pub fn snapshot(&mut self, params: &FizzLaunchParams) {
+        self.virtual_sol = params.virtual_sol;
+        self.fee_bps = params.admin_fee_bps;
+        self.graduation_sol = params.graduation_sol;
+        self.creator_bonus_bps = params.admin_creator_bonus_bps;
+    }
+
+    /// Record a CAPS launch tier and its perks, the only source of a CAPS
+    /// launch's trading fee and creator bonus
+    pub fn apply_tier(&mut self, tier: &FizzLaunchTier) {
+        self.launch_type = tier.launch_type;
+        self.fee_bps = tier.fee_bps;
+        self.creator_bonus_bps = tier.creator_bonus_bps;
+    }
+
+    /// Quote selling `token_amount` back to the curve and book it: the gross
+    /// SOL (seller payout plus fee) leaves `sol_reserve`, the tokens return
+    pub fn sell(&mut self, token_amount: u64) -> Result<SellQuote> {
+        require!(!self.graduated, FizzError::TokenGraduated);
+        require!(token_amount > 0, FizzError::ZeroAmount);
+        let quote = curve_math::quote_sell(self.reserves(), token_amount, self.fee_bps)
+            .map_err(FizzError::from)?;
+        self.sol_reserve = self
+            .sol_reserve
+            .checked_sub(quote.sol_out_gross)
+            .ok_or(FizzError::MathOverflow)?;
+        self.token_reserve = self
+            .token_reserve
+            .checked_add(token_amount)
+            .ok_or(FizzError::MathOverflow)?;
+        Ok(quote)
+    }
+
+    /// Inputs for `curve_math` quotes
+    pub fn reserves(&self) -> CurveReserves {
+        CurveReserves {
+            sol_reserve: self.sol_reserve,
+            virtual_sol: self.virtual_sol,
+            token_reserve: self.token_reserve,
+        }
+    }
+}
+
+// ============ ACCOUNT CONTEXTS ============
+
+/// Accounts every single-voucher claim path shares; see `prepare_claim`
+#[derive(Accounts)]
+#[instruction(voucher: LootVoucher)]
+pub struct ClaimLootCommon<'info> {
+    /// Pays rent for everything the claim creates; may be a game-run relayer
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+
+    /// Authorizes the fee (CAPS or SOL) and receives the loot
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut)]
+    pub player_caps_ata: Account<'info, TokenAccount>,
+
+    #[account(mut, seeds = [CAPS_MINT_SEEDS], bump)]
+    pub caps_mint: Account<'info, Mint>,
+
+    /// CHECK: Server verification key, checked against `signer_registry`
+    pub server_key: AccountInfo<'info>,
+
+    #[account(seeds = [SIGNER_REGISTRY_SEEDS], bump = signer_registry.bump)]
+    pub signer_registry: Account<'info, SignerRegistry>,
+
+    #[account(seeds = [BEACON_REGISTRY_SEEDS], bump = beacon_registry.bump)]
+    pub beacon_registry: Box<Account<'info, BeaconRegistry>>,
+
+    #[account(seeds = [LOOT_CONFIG_SEEDS], bump = loot_config.bump)]
+    pub loot_config: Account<'info, LootConfig>,
+
+    #[account(seeds = [LOOT_METADATA_CONFIG_SEEDS], bump = loot_metadata_config.bump)]
+    pub loot_metadata_config: Box<Account<'info, LootMetadataConfig>>,
+
+    #[account(mut, address = loot_config.treasury)]
+    pub treasury_caps_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: SOL fee recipient
+    #[account(mut, address = loot_config.sol_treasury)]
+    pub sol_treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = LOOT_PLAYER_SPACE,
+        seeds = [LOOT_PLAYER_SEEDS, player.key().as_ref()],
+        bump
+    )]
+    pub player_state: Account<'info, LootPlayer>,
+
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = LOCATION_CELL_SPACE,
+        seeds = [LOCATION_CELL_SEEDS, loot_config.cell_key(&voucher.payload).as_ref()],
+        bump
+    )]
+    pub location_cell: Box<Account<'info, LocationCell>>,
+
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = CELL_COOLDOWN_SPACE,
+        seeds = [
+            CELL_COOLDOWN_SEEDS,
+            player.key().as_ref(),
+            loot_config.cell_key(&voucher.payload).as_ref()
+        ],
+        bump
+    )]
+    pub cell_cooldown: Box<Account<'info, CellCooldown>>,
+
+    /// CHECK: LootItem PDA for the voucher's loot id, created by `reserve_loot_item`
+    #[account(mut)]
+    pub loot_item: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimLoot<'info> {
+    pub claim: ClaimLootCommon<'info>,
+
+    /// CHECK: Loot id mint PDA, created by `init_loot_mint_and_ata`
+    #[account(mut)]
+    pub loot_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Player's ATA for `loot_mint`, created by `init_loot_mint_and_ata`
+    #[account(mut)]
+    pub player_loot_ata: UncheckedAccount<'info>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata PDA
+    #[account(mut)]
+    pub loot_metadata: UncheckedAccount<'info>,
+
+    #[account(constraint = loot_collection.active @ ErrorCode::CollectionInactive)]
+    pub loot_collection: Box<Account<'info, LootCollection>>,
+
+    /// CHECK: Collection mint recorded in `loot_collection`
+    #[account(address = loot_collection.mint)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Collection metadata PDA, checked by the metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Collection master edition PDA, checked by the metadata program
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(address = METADATA_PROGRAM_ID)]
+    pub metadata_program: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimLootBatch<'info> {
+    /// Pays rent for everything the claim creates; may be a game-run relayer
+    #[account(mut)]
+    pub fee_payer: Signer<'info>,
+
+    /// Authorizes the fee (CAPS or SOL) and receives the loot
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(mut)]
+    pub player_caps_ata: Account<'info, TokenAccount>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    #[account(mut, seeds = [CAPS_MINT_SEEDS], bump)]
+    pub caps_mint: Account<'info, Mint>,
+
+    /// CHECK: Server verification key, checked against `signer_registry`
+    pub server_key: AccountInfo<'info>,
+
+    #[account(seeds = [SIGNER_REGISTRY_SEEDS], bump = signer_registry.bump)]
+    pub signer_registry: Account<'info, SignerRegistry>,
+
+    #[account(seeds = [LOOT_CONFIG_SEEDS], bump = loot_config.bump)]
+    pub loot_config: Account<'info, LootConfig>,
+
+    #[account(seeds = [LOOT_METADATA_CONFIG_SEEDS], bump = loot_metadata_config.bump)]
+    pub loot_metadata_config: Box<Account<'info, LootMetadataConfig>>,
+
+    #[account(mut, address = loot_config.treasury)]
+    pub treasury_caps_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: SOL fee recipient
+    #[account(mut, address = loot_config.sol_treasury)]
+    pub sol_treasury: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = fee_payer,
+        space = LOOT_PLAYER_SPACE,
+        seeds = [LOOT_PLAYER_SEEDS, player.key().as_ref()],
+        bump
+    )]
+    pub player_state: Account<'info, LootPlayer>,
+
+    #[account(constraint = loot_collection.active @ ErrorCode::CollectionInactive)]
+    pub loot_collection: Box<Account<'info, LootCollection>>,
+
+    /// CHECK: Collection mint recorded in `loot_collection`
+    #[account(address = loot_collection.mint)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Collection metadata PDA, checked by the metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Collection master edition PDA, checked by the metadata program
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(address = METADATA_PROGRAM_ID)]
+    pub metadata_program: UncheckedAccount<'info>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct ClaimLootCompressed<'info> {
+    pub claim: ClaimLootCommon<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOOT_TREE_SEEDS, loot_tree.tree_id.to_le_bytes().as_ref()],
+        bump = loot_tree.bump
+    )]
+    pub loot_tree: Box<Account<'info, LootTree>>,
+}
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(voucher: LootVoucher)]
+pub struct ClaimLootCore<'info> {
+    pub claim: ClaimLootCommon<'info>,
+
+    /// CHECK: Created by mpl-core; the loot id PDA makes each cache claimable once
+    #[account(
+        mut,
+        seeds = [LOOT_ASSET_SEEDS, voucher.payload.loot_id().to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_asset: UncheckedAccount<'info>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: mpl-core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevealLootLocation<'info> {
+    pub player: Signer<'info>,
+
+    #[account(
+        seeds = [LOOT_ITEM_SEEDS, loot_item.loot_id.to_le_bytes().as_ref()],
+        bump = loot_item.bump,
+        constraint = loot_item.claimed_by == player.key() @ ErrorCode::NotLootClaimer
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
+}
+
+#[derive(Accounts)]
+pub struct InitLootConfig<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 1 + 8 + 2 + 32 + 1 + 1 + 8 + 4 + 8 + 8 + 32 + 8 + 1 + 1 + 8 + 1 + 2 + 8 + 1,
+        seeds = [LOOT_CONFIG_SEEDS],
+        bump
+    )]
+    pub loot_config: Account<'info, LootConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageLootConfig<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [LOOT_CONFIG_SEEDS], bump = loot_config.bump)]
+    pub loot_config: Account<'info, LootConfig>,
+}
+
+#[derive(Accounts)]
+pub struct InitLootMetadataConfig<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 2 * (4 + MAX_NAME_TEMPLATE_LEN) + (4 + 10) + (4 + 200) + 1,
+        seeds = [LOOT_METADATA_CONFIG_SEEDS],
+        bump
+    )]
+    pub loot_metadata_config: Account<'info, LootMetadataConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageLootMetadataConfig<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [LOOT_METADATA_CONFIG_SEEDS], bump = loot_metadata_config.bump)]
+    pub loot_metadata_config: Account<'info, LootMetadataConfig>,
+}
+
+#[derive(Accounts)]
+#[instruction(tree_id: u32)]
+pub struct InitLootTree<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 4 + merkle::MerkleTree::SIZE + 1,
+        seeds = [LOOT_TREE_SEEDS, tree_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_tree: Box<Account<'info, LootTree>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(season: u16)]
+pub struct CreateLootCollection<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 2 + 33 + 1 + 1,
+        seeds = [LOOT_COLLECTION_SEEDS, season.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_collection: Account<'info, LootCollection>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = loot_mint_authority,
+        mint::freeze_authority = loot_mint_authority,
+        seeds = [LOOT_COLLECTION_MINT_SEEDS, season.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub collection_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = loot_mint_authority
+    )]
+    pub collection_token_account: Account<'info, TokenAccount>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata PDA
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Master edition PDA
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// Root collection, required for seasonal sub-collections
+    pub parent_collection: Option<Account<'info, LootCollection>>,
+
+    /// CHECK: Root collection mint, checked against `parent_collection`
+    pub parent_collection_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Root collection metadata, checked by the metadata program
+    #[account(mut)]
+    pub parent_collection_metadata: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Root collection master edition, checked by the metadata program
+    pub parent_collection_master_edition: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(address = METADATA_PROGRAM_ID)]
+    pub metadata_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ManageLootCollection<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        mut,
+        seeds = [LOOT_COLLECTION_SEEDS, loot_collection.season.to_le_bytes().as_ref()],
+        bump = loot_collection.bump
+    )]
+    pub loot_collection: Account<'info, LootCollection>,
+}
+
+#[derive(Accounts)]
+pub struct InitSignerRegistry<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 4 + MAX_VOUCHER_SIGNERS * (32 + 8 + 8 + 1) + 1 + 1 + 1,
+        seeds = [SIGNER_REGISTRY_SEEDS],
+        bump
+    )]
+    pub signer_registry: Account<'info, SignerRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageVoucherSigners<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [SIGNER_REGISTRY_SEEDS], bump = signer_registry.bump)]
+    pub signer_registry: Account<'info, SignerRegistry>,
+}
+
+#[derive(Accounts)]
+pub struct InitItemState<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [LOOT_ITEM_SEEDS, loot_item.loot_id.to_le_bytes().as_ref()],
+        bump = loot_item.bump,
+        constraint = !loot_item.burned @ ErrorCode::LootItemBurned
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
+
+    #[account(seeds = [LOOT_CONFIG_SEEDS], bump = loot_config.bump)]
+    pub loot_config: Account<'info, LootConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = LOOT_ITEM_STATE_SPACE,
+        seeds = [LOOT_ITEM_STATE_SEEDS, loot_item.loot_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub item_state: Account<'info, LootItemState>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyItemWear<'info> {
+    /// Pays for Core plugin reallocation
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOOT_ITEM_STATE_SEEDS, item_state.loot_id.to_le_bytes().as_ref()],
+        bump = item_state.bump
+    )]
+    pub item_state: Account<'info, LootItemState>,
+
+    #[account(
+        seeds = [LOOT_ITEM_SEEDS, item_state.loot_id.to_le_bytes().as_ref()],
+        bump = loot_item.bump,
+        constraint = !loot_item.burned @ ErrorCode::LootItemBurned
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
+
+    /// CHECK: Server verification key, checked against `signer_registry`
+    pub server_key: AccountInfo<'info>,
+
+    #[account(seeds = [SIGNER_REGISTRY_SEEDS], bump = signer_registry.bump)]
+    pub signer_registry: Account<'info, SignerRegistry>,
+
+    /// CHECK: Core asset, required when `item_state.is_core_asset`
+    #[account(mut, address = item_state.mint)]
+    pub loot_asset: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: mpl-core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RepairItem<'info> {
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [LOOT_ITEM_STATE_SEEDS, item_state.loot_id.to_le_bytes().as_ref()],
+        bump = item_state.bump
+    )]
+    pub item_state: Account<'info, LootItemState>,
+
+    #[account(
+        seeds = [LOOT_ITEM_SEEDS, item_state.loot_id.to_le_bytes().as_ref()],
+        bump = loot_item.bump,
+        constraint = !loot_item.burned @ ErrorCode::LootItemBurned
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
+
+    #[account(seeds = [LOOT_CONFIG_SEEDS], bump = loot_config.bump)]
+    pub loot_config: Account<'info, LootConfig>,
+
+    #[account(mut, seeds = [CAPS_MINT_SEEDS], bump)]
+    pub caps_mint: Account<'info, Mint>,
+
+    #[account(mut, token::mint = caps_mint, token::authority = player)]
+    pub player_caps_ata: Account<'info, TokenAccount>,
+
+    /// CHECK: Core asset, required when `item_state.is_core_asset`
+    #[account(mut, address = item_state.mint)]
+    pub loot_asset: Option<UncheckedAccount<'info>>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: mpl-core program
+    #[account(address = mpl_core::ID)]
+    pub mpl_core_program: Option<UncheckedAccount<'info>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitFusionRecipes<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LootRarity::COUNT * 2 + 1,
+        seeds = [FUSION_RECIPES_SEEDS],
+        bump
+    )]
+    pub fusion_recipes: Account<'info, FusionRecipes>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageFusionRecipes<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [FUSION_RECIPES_SEEDS], bump = fusion_recipes.bump)]
+    pub fusion_recipes: Account<'info, FusionRecipes>,
+}
+
+#[derive(Accounts)]
+#[instruction(fused_loot_id: u64)]
+pub struct FuseLoot<'info> {
+    /// Owns the inputs and pays rent for the pending fusion
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(seeds = [FUSION_RECIPES_SEEDS], bump = fusion_recipes.bump)]
+    pub fusion_recipes: Box<Account<'info, FusionRecipes>>,
+
+    #[account(
+        init,
+        payer = player,
+        space = PENDING_FUSION_SPACE,
+        seeds = [PENDING_FUSION_SEEDS, fused_loot_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub pending_fusion: Box<Account<'info, PendingFusion>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(fused_loot_id: u64)]
+pub struct RevealFusion<'info> {
+    /// Fused the inputs, pays rent for the output and receives it
+    #[account(mut)]
+    pub player: Signer<'info>,
+
+    #[account(
+        mut,
+        close = player,
+        constraint = pending_fusion.player == player.key() @ ErrorCode::NotLootOwner,
+        seeds = [PENDING_FUSION_SEEDS, fused_loot_id.to_le_bytes().as_ref()],
+        bump = pending_fusion.bump
+    )]
+    pub pending_fusion: Box<Account<'info, PendingFusion>>,
+
+    #[account(
+        init,
+        payer = player,
+        mint::decimals = 0,
+        mint::authority = loot_mint_authority,
+        seeds = [LOOT_MINT_AUTHORITY_SEEDS, fused_loot_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_mint: Box<Account<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = loot_mint,
+        associated_token::authority = player
+    )]
+    pub player_loot_ata: Box<Account<'info, TokenAccount>>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata PDA
+    #[account(mut)]
+    pub loot_metadata: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = player,
+        space = LOOT_ITEM_SPACE,
+        seeds = [LOOT_ITEM_SEEDS, fused_loot_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
+
+    #[account(seeds = [LOOT_METADATA_CONFIG_SEEDS], bump = loot_metadata_config.bump)]
+    pub loot_metadata_config: Box<Account<'info, LootMetadataConfig>>,
+
+    #[account(constraint = loot_collection.active @ ErrorCode::CollectionInactive)]
+    pub loot_collection: Box<Account<'info, LootCollection>>,
+
+    /// CHECK: Collection mint recorded in `loot_collection`
+    #[account(address = loot_collection.mint)]
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Collection metadata PDA, checked by the metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Collection master edition PDA, checked by the metadata program
+    pub collection_master_edition: UncheckedAccount<'info>,
+
+    /// CHECK: SlotHashes sysvar, read raw for the commit slot's hash
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions_sysvar: AccountInfo<'info>,
+
+    #[account(address = METADATA_PROGRAM_ID)]
+    pub metadata_program: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct InitBeaconRegistry<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 4 + MAX_BEACONS * 32 + 1,
+        seeds = [BEACON_REGISTRY_SEEDS],
+        bump
+    )]
+    pub beacon_registry: Account<'info, BeaconRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ManageBeacons<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [BEACON_REGISTRY_SEEDS], bump = beacon_registry.bump)]
+    pub beacon_registry: Account<'info, BeaconRegistry>,
+}
+
+#[derive(Accounts)]
+#[instruction(witness: Pubkey)]
+pub struct RegisterWitness<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + 32 + 1,
+        seeds = [LOOT_WITNESS_SEEDS, witness.as_ref()],
+        bump
+    )]
+    pub loot_witness: Account<'info, LootWitness>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemoveWitness<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [LOOT_WITNESS_SEEDS, loot_witness.witness.as_ref()],
+        bump = loot_witness.bump
+    )]
+    pub loot_witness: Account<'info, LootWitness>,
+}
+
+#[derive(Accounts)]
+pub struct InitSalvageTable<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + LootRarity::COUNT * (8 + 8) + 1 + 1,
+        seeds = [SALVAGE_TABLE_SEEDS],
+        bump
+    )]
+    pub salvage_table: Account<'info, SalvageTable>,
+
+    #[account(
+        init,
+        payer = authority,
+        token::mint = caps_mint,
+        token::authority = salvage_pool,
+        seeds = [SALVAGE_POOL_SEEDS],
+        bump
+    )]
+    pub salvage_pool: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = authority,
+        mint::decimals = 0,
+        mint::authority = loot_mint_authority,
+        seeds = [MATERIAL_MINT_SEEDS],
+        bump
+    )]
+    pub material_mint: Account<'info, Mint>,
+
+    #[account(seeds = [CAPS_MINT_SEEDS], bump)]
+    pub caps_mint: Account<'info, Mint>,
 
-/// Launch type for transparency (stored on-chain)
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
-pub enum FizzLaunchType {
-    CapsStandard,  // Regular launch (100 CAPS burned)
-    CapsVeteran,   // Veteran launch (50 CAPS burned)
-    AdminUSDC,     // Admin bootstrap (USDC paid off-chain)
-    AdminFree,     // Official tokens
-}
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
 
-/// Fizz.fun global config
-#[account]
-pub struct FizzConfig {
-    pub authority: Pubkey,
-    pub treasury: Pubkey,
-    pub caps_mint: Pubkey,
-    pub total_tokens_launched: u64,
-    pub total_volume_sol: u64,
-    pub total_caps_burned: u64,
-    pub admin_usdc_launches: u64,
-    pub bump: u8,
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
 }
 
-/// Admin record
-#[account]
-pub struct FizzAdminRecord {
-    pub admin: Pubkey,
-    pub added_at: i64,
-    pub is_active: bool,
-    pub bump: u8,
-}
+#[derive(Accounts)]
+pub struct ManageSalvageTable<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
 
-/// Bonding curve state - OPTIMIZED for minimal rent (~0.002 SOL)
-/// Name, symbol, URI stored off-chain (indexed from events)
-#[account]
-pub struct FizzBondingCurve {
-    pub creator: Pubkey,      // 32 bytes
-    pub token_mint: Pubkey,   // 32 bytes
-    pub sol_reserve: u64,     // 8 bytes
-    pub token_reserve: u64,   // 8 bytes
-    pub graduated: bool,      // 1 byte
-    pub created_at: i64,      // 8 bytes
-    pub launch_type: FizzLaunchType, // 1 byte
-    pub bump: u8,             // 1 byte
-    // TOTAL: 8 (discriminator) + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 1 = 99 bytes
-    // Rent: ~0.0016 SOL
-}
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
 
-// ============ ACCOUNT CONTEXTS ============
+    #[account(mut, seeds = [SALVAGE_TABLE_SEEDS], bump = salvage_table.bump)]
+    pub salvage_table: Account<'info, SalvageTable>,
+}
 
 #[derive(Accounts)]
-#[instruction(voucher: LootVoucher)]
-pub struct ClaimLoot<'info> {
+pub struct SalvageLoot<'info> {
     #[account(mut)]
     pub player: Signer<'info>,
 
-    #[account(mut)]
-    pub player_caps_ata: Account<'info, TokenAccount>,
+    /// Marked burned, never closed, so the loot id can't be claimed again
+    #[account(
+        mut,
+        seeds = [LOOT_ITEM_SEEDS, loot_item.loot_id.to_le_bytes().as_ref()],
+        bump = loot_item.bump
+    )]
+    pub loot_item: Box<Account<'info, LootItem>>,
 
+    /// Only mints derived from a loot id by this program are FIZZLOOT
     #[account(
-        init,
-        payer = player,
-        mint::decimals = 0,
-        mint::authority = loot_mint_authority,
-        seeds = [LOOT_MINT_AUTHORITY_SEEDS, voucher.loot_id.to_le_bytes().as_ref()],
-        bump
+        mut,
+        seeds = [LOOT_MINT_AUTHORITY_SEEDS, loot_item.loot_id.to_le_bytes().as_ref()],
+        bump,
+        constraint = loot_mint.key() == loot_item.mint @ ErrorCode::LootItemMismatch
     )]
     pub loot_mint: Account<'info, Mint>,
 
     #[account(
-        init_if_needed,
-        payer = player,
+        mut,
         associated_token::mint = loot_mint,
-        associated_token::authority = player
+        associated_token::authority = player,
+        constraint = player_loot_ata.amount == 1 @ ErrorCode::NotLootOwner
     )]
     pub player_loot_ata: Account<'info, TokenAccount>,
 
-    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
-    pub loot_mint_authority: UncheckedAccount<'info>,
+    #[account(seeds = [SALVAGE_TABLE_SEEDS], bump = salvage_table.bump)]
+    pub salvage_table: Account<'info, SalvageTable>,
+
+    #[account(mut, seeds = [SALVAGE_POOL_SEEDS], bump = salvage_table.pool_bump)]
+    pub salvage_pool: Account<'info, TokenAccount>,
+
+    #[account(mut, token::mint = caps_mint)]
+    pub player_caps_ata: Account<'info, TokenAccount>,
 
     #[account(seeds = [CAPS_MINT_SEEDS], bump)]
     pub caps_mint: Account<'info, Mint>,
 
-    /// CHECK: Server verification key
-    pub server_key: AccountInfo<'info>,
+    #[account(mut, seeds = [MATERIAL_MINT_SEEDS], bump)]
+    pub material_mint: Account<'info, Mint>,
 
-    /// CHECK: Metadata PDA
-    #[account(mut)]
-    pub loot_metadata: UncheckedAccount<'info>,
+    #[account(
+        init_if_needed,
+        payer = player,
+        associated_token::mint = material_mint,
+        associated_token::authority = player
+    )]
+    pub player_material_ata: Account<'info, TokenAccount>,
+
+    #[account(seeds = [LOOT_MINT_AUTHORITY_SEEDS], bump)]
+    pub loot_mint_authority: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
-
-    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
-    pub instructions_sysvar: AccountInfo<'info>,
-
-    #[account(address = METADATA_PROGRAM_ID)]
-    pub metadata_program: UncheckedAccount<'info>,
 }
 
 #[derive(Accounts)]
@@ -643,7 +5036,7 @@ pub struct FizzInit<'info> {
     #[account(
         init,
         payer = authority,
-        space = 8 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 1,
+        space = FizzConfig::SPACE,
         seeds = [FIZZ_CONFIG_SEEDS],
         bump
     )]
@@ -656,6 +5049,15 @@ pub struct FizzInit<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct FizzManageConfig<'info> {
+    #[account(constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(mut, seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+}
+
 #[derive(Accounts)]
 #[instruction(admin: Pubkey)]
 pub struct FizzManageAdmin<'info> {
@@ -707,7 +5109,7 @@ pub struct FizzCreateToken<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 1, // 99 bytes = ~0.0016 SOL rent
+        space = FizzBondingCurve::SPACE, // 131 bytes = ~0.0018 SOL rent
         seeds = [FIZZ_CURVE_SEEDS, token_mint.key().as_ref()],
         bump
     )]
@@ -721,6 +5123,10 @@ pub struct FizzCreateToken<'info> {
     )]
     pub curve_token_vault: Account<'info, TokenAccount>,
 
+    /// CHECK: SOL vault PDA, funded to rent exemption here
+    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, token_mint.key().as_ref()], bump)]
+    pub curve_sol_vault: AccountInfo<'info>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
@@ -754,7 +5160,7 @@ pub struct FizzCreateTokenAdmin<'info> {
     #[account(
         init,
         payer = creator,
-        space = 8 + 32 + 32 + 8 + 8 + 1 + 8 + 1 + 1, // 99 bytes = ~0.0016 SOL rent
+        space = FizzBondingCurve::SPACE, // 131 bytes = ~0.0018 SOL rent
         seeds = [FIZZ_CURVE_SEEDS, token_mint.key().as_ref()],
         bump
     )]
@@ -768,109 +5174,396 @@ pub struct FizzCreateTokenAdmin<'info> {
     )]
     pub curve_token_vault: Account<'info, TokenAccount>,
 
-    pub token_program: Program<'info, Token>,
-    pub associated_token_program: Program<'info, AssociatedToken>,
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
+    /// CHECK: SOL vault PDA, funded to rent exemption here
+    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, token_mint.key().as_ref()], bump)]
+    pub curve_sol_vault: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct FizzBuyTokens<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    #[account(mut, seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        mut,
+        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, FizzBondingCurve>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub curve_token_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: SOL vault PDA
+    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
+    pub curve_sol_vault: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = token_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_ata: Account<'info, TokenAccount>,
+
+    #[account(address = bonding_curve.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Treasury
+    #[account(mut, address = config.treasury)]
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FizzSellTokens<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Account<'info, FizzBondingCurve>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub curve_token_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: SOL vault PDA
+    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
+    pub curve_sol_vault: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub seller_token_ata: Account<'info, TokenAccount>,
+
+    #[account(address = bonding_curve.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+
+    /// CHECK: Treasury
+    #[account(mut, address = config.treasury)]
+    pub treasury: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FizzGraduate<'info> {
+    /// Anyone can crank graduation; pays rent for the pool accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
+        bump = bonding_curve.bump
+    )]
+    pub bonding_curve: Box<Account<'info, FizzBondingCurve>>,
+
+    #[account(mut, address = bonding_curve.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Creator gets bonus
+    #[account(mut, address = bonding_curve.creator)]
+    pub creator: AccountInfo<'info>,
+
+    /// CHECK: SOL vault
+    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
+    pub curve_sol_vault: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = token_mint,
+        associated_token::authority = bonding_curve,
+    )]
+    pub curve_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = FizzPool::SPACE,
+        seeds = [FIZZ_POOL_SEEDS, token_mint.key().as_ref()],
+        bump
+    )]
+    pub pool: Box<Account<'info, FizzPool>>,
+
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = token_mint,
+        associated_token::authority = pool,
+    )]
+    pub pool_token_vault: Box<Account<'info, TokenAccount>>,
+
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 9,
+        mint::authority = pool,
+        seeds = [FIZZ_LP_MINT_SEEDS, token_mint.key().as_ref()],
+        bump
+    )]
+    pub lp_mint: Box<Account<'info, Mint>>,
+
+    /// Holds the initial LP forever
+    #[account(
+        init,
+        payer = payer,
+        associated_token::mint = lp_mint,
+        associated_token::authority = pool,
+    )]
+    pub locked_lp_vault: Box<Account<'info, TokenAccount>>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FizzInitBuyback<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(init, payer = authority, space = 8 + 8 + 1, seeds = [FIZZ_BUYBACK_SEEDS], bump)]
+    pub buyback: Account<'info, FizzBuyback>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FizzWithdrawBuyback<'info> {
+    #[account(mut, constraint = config.authority == authority.key() @ FizzError::NotAuthority)]
+    pub authority: Signer<'info>,
+
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Account<'info, FizzConfig>,
+
+    #[account(mut, seeds = [FIZZ_BUYBACK_SEEDS], bump = buyback.bump)]
+    pub buyback: Account<'info, FizzBuyback>,
 }
 
 #[derive(Accounts)]
-pub struct FizzBuyTokens<'info> {
+pub struct PoolSwap<'info> {
     #[account(mut)]
-    pub buyer: Signer<'info>,
+    pub trader: Signer<'info>,
 
-    #[account(mut, seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
-    pub config: Account<'info, FizzConfig>,
+    #[account(seeds = [FIZZ_CONFIG_SEEDS], bump = config.bump)]
+    pub config: Box<Account<'info, FizzConfig>>,
 
-    #[account(
-        mut,
-        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
-        bump = bonding_curve.bump
-    )]
-    pub bonding_curve: Account<'info, FizzBondingCurve>,
+    #[account(mut, seeds = [FIZZ_POOL_SEEDS, pool.token_mint.as_ref()], bump = pool.bump)]
+    pub pool: Box<Account<'info, FizzPool>>,
 
-    #[account(mut)]
-    pub curve_token_vault: Account<'info, TokenAccount>,
+    #[account(address = pool.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
 
-    /// CHECK: SOL vault PDA
-    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
-    pub curve_sol_vault: AccountInfo<'info>,
+    #[account(mut, address = pool.token_vault)]
+    pub pool_token_vault: Box<Account<'info, TokenAccount>>,
 
     #[account(
         init_if_needed,
-        payer = buyer,
+        payer = trader,
         associated_token::mint = token_mint,
-        associated_token::authority = buyer,
+        associated_token::authority = trader,
     )]
-    pub buyer_token_ata: Account<'info, TokenAccount>,
-
-    pub token_mint: Account<'info, Mint>,
+    pub trader_token_ata: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: Treasury
     #[account(mut, address = config.treasury)]
     pub treasury: AccountInfo<'info>,
 
+    #[account(mut, seeds = [FIZZ_BUYBACK_SEEDS], bump = buyback.bump)]
+    pub buyback: Account<'info, FizzBuyback>,
+
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
+/// Shared by `pool_add_liquidity` and `pool_remove_liquidity`
 #[derive(Accounts)]
-pub struct FizzSellTokens<'info> {
+pub struct PoolLiquidity<'info> {
     #[account(mut)]
-    pub seller: Signer<'info>,
+    pub provider: Signer<'info>,
 
-    #[account(
-        mut,
-        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
-        bump = bonding_curve.bump
-    )]
-    pub bonding_curve: Account<'info, FizzBondingCurve>,
+    #[account(mut, seeds = [FIZZ_POOL_SEEDS, pool.token_mint.as_ref()], bump = pool.bump)]
+    pub pool: Box<Account<'info, FizzPool>>,
 
-    #[account(mut)]
-    pub curve_token_vault: Account<'info, TokenAccount>,
+    #[account(address = pool.token_mint)]
+    pub token_mint: Box<Account<'info, Mint>>,
 
-    /// CHECK: SOL vault PDA
-    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
-    pub curve_sol_vault: AccountInfo<'info>,
+    #[account(mut, address = pool.token_vault)]
+    pub pool_token_vault: Box<Account<'info, TokenAccount>>,
 
-    #[account(mut)]
-    pub seller_token_ata: Account<'info, TokenAccount>,
+    #[account(mut, address = pool.lp_mint)]
+    pub lp_mint: Box<Account<'info, Mint>>,
 
-    pub token_mint: Account<'info, Mint>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = token_mint,
+        associated_token::authority = provider,
+    )]
+    pub provider_token_ata: Box<Account<'info, TokenAccount>>,
 
-    /// CHECK: Treasury
-    #[account(mut)]
-    pub treasury: AccountInfo<'info>,
+    #[account(
+        init_if_needed,
+        payer = provider,
+        associated_token::mint = lp_mint,
+        associated_token::authority = provider,
+    )]
+    pub provider_lp_ata: Box<Account<'info, TokenAccount>>,
 
     pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-#[derive(Accounts)]
-pub struct FizzGraduate<'info> {
-    #[account(
-        mut,
-        seeds = [FIZZ_CURVE_SEEDS, bonding_curve.token_mint.as_ref()],
-        bump = bonding_curve.bump
-    )]
-    pub bonding_curve: Account<'info, FizzBondingCurve>,
+// ============ EVENTS ============
 
-    /// CHECK: Creator gets bonus
-    #[account(mut, address = bonding_curve.creator)]
-    pub creator: AccountInfo<'info>,
+#[event]
+pub struct LootConfigUpdated {
+    pub authority: Pubkey,
+    pub params: LootConfigParams,
+    pub timestamp: i64,
+}
 
-    /// CHECK: SOL vault
-    #[account(mut, seeds = [FIZZ_SOL_VAULT_SEEDS, bonding_curve.token_mint.as_ref()], bump)]
-    pub curve_sol_vault: AccountInfo<'info>,
+/// Emitted with `emit_cpi!` so it survives log truncation
+#[event]
+pub struct LootClaimed {
+    pub loot_id: u64,
+    pub player: Pubkey,
+    /// Same as `LootItem.mint`: SPL mint, Core asset or loot tree
+    pub mint: Pubkey,
+    pub payment: LootPaymentMethod,
+    /// CAPS burned for this claim (0 if it used a free daily claim or paid in SOL)
+    pub fee_burned: u64,
+    /// Key that signed the voucher
+    pub signer: Pubkey,
+    /// `None` for private claims
+    pub lat_micro: Option<i32>,
+    pub lon_micro: Option<i32>,
+    pub location_commitment: Option<[u8; 32]>,
+    pub timestamp: i64,
+}
 
-    #[account(mut)]
-    pub curve_token_vault: Account<'info, TokenAccount>,
+#[event]
+pub struct CompressedLootMinted {
+    pub tree: Pubkey,
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    pub root: [u8; 32],
+    pub loot_id: u64,
+    pub owner: Pubkey,
+    pub roll: LootRoll,
+    pub name: String,
+    pub uri: String,
+    pub location_commitment: Option<[u8; 32]>,
+    pub payment: LootPaymentMethod,
+    /// CAPS burned for this claim (0 if it used a free daily claim or paid in SOL)
+    pub fee_burned: u64,
+}
 
-    pub system_program: Program<'info, System>,
+#[event]
+pub struct LootLocationRevealed {
+    pub loot_id: u64,
+    pub player: Pubkey,
+    pub lat_micro: i32,
+    pub lon_micro: i32,
+    pub geohash: [u8; geo::GEOHASH_LEN],
+    pub timestamp: i64,
 }
 
-// ============ EVENTS ============
+#[event]
+pub struct LootMetadataConfigUpdated {
+    pub authority: Pubkey,
+    pub params: LootMetadataParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LootSalvaged {
+    pub loot_id: u64,
+    pub player: Pubkey,
+    pub mint: Pubkey,
+    pub rarity: LootRarity,
+    pub caps_paid: u64,
+    pub materials_minted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LootItemWorn {
+    pub loot_id: u64,
+    pub mint: Pubkey,
+    pub durability: u16,
+    pub level: u8,
+    pub mods: Vec<u16>,
+    pub nonce: u64,
+    pub signer: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LootItemRepaired {
+    pub loot_id: u64,
+    pub mint: Pubkey,
+    pub player: Pubkey,
+    pub restored: u16,
+    pub caps_burned: u64,
+    pub durability: u16,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct LootFused {
+    pub player: Pubkey,
+    pub burned_ids: Vec<u64>,
+    pub input_rarity: LootRarity,
+    pub loot_id: u64,
+    pub mint: Pubkey,
+    pub rarity: LootRarity,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FizzConfigUpdated {
+    pub authority: Pubkey,
+    pub params: FizzLaunchParams,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FizzLaunchTiersUpdated {
+    pub authority: Pubkey,
+    pub tiers: Vec<FizzLaunchTier>,
+    pub timestamp: i64,
+}
 
 #[event]
 pub struct FizzTokenCreated {
@@ -880,6 +5573,10 @@ pub struct FizzTokenCreated {
     pub symbol: String,
     pub launch_type: FizzLaunchType,
     pub caps_burned: u64,
+    /// Tier trading fee on this curve
+    pub fee_bps: u64,
+    /// Tier creator bonus at graduation
+    pub creator_bonus_bps: u64,
     pub timestamp: i64,
 }
 
@@ -923,6 +5620,54 @@ pub struct FizzTokenGraduated {
     pub creator_bonus: u64,
 }
 
+#[event]
+pub struct FizzLiquidityMigrated {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub lp_mint: Pubkey,
+    pub sol_liquidity: u64,
+    pub token_liquidity: u64,
+    pub tokens_burned: u64,
+    pub lp_locked: u64,
+    /// Lamports per whole token
+    pub initial_price: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FizzPoolSwapped {
+    pub mint: Pubkey,
+    pub pool: Pubkey,
+    pub trader: Pubkey,
+    pub direction: FizzSwapDirection,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub lp_fee: u64,
+    pub treasury_fee: u64,
+    pub buyback_fee: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FizzLiquidityAdded {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_minted: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FizzLiquidityRemoved {
+    pub mint: Pubkey,
+    pub provider: Pubkey,
+    pub sol_amount: u64,
+    pub token_amount: u64,
+    pub lp_burned: u64,
+    pub timestamp: i64,
+}
+
 // ============ ERRORS ============
 
 #[error_code]
@@ -939,17 +5684,145 @@ pub enum ErrorCode {
     WrongSignature,
     #[msg("Ed25519 signed message does not match voucher")]
     WrongMessage,
+    #[msg("Voucher signer is not registered")]
+    UnregisteredSigner,
+    #[msg("Voucher signer has been revoked")]
+    SignerRevoked,
+    #[msg("Voucher signer is outside its validity window")]
+    SignerExpired,
+    #[msg("Signer validity window is empty")]
+    InvalidSignerWindow,
+    #[msg("Signer registry is full")]
+    SignerRegistryFull,
+    #[msg("V1 vouchers are no longer accepted")]
+    LegacyVoucherDisabled,
+    #[msg("Voucher is bound to a different player")]
+    VoucherWrongPlayer,
+    #[msg("Voucher was signed for a different program or cluster")]
+    VoucherWrongDomain,
+    #[msg("Voucher has expired")]
+    VoucherExpired,
+    #[msg("Voucher nonce already used")]
+    VoucherNonceUsed,
+    #[msg("Batch contains no vouchers")]
+    EmptyBatch,
+    #[msg("Too many vouchers in one batch")]
+    BatchTooLarge,
+    #[msg("Batch needs the per-voucher accounts listed on claim_loot_batch")]
+    BatchAccountsMismatch,
+    #[msg("Loot mint is not the PDA for this voucher's loot id")]
+    LootMintAddressMismatch,
+    #[msg("Loot token account is not the player's ATA")]
+    LootAtaMismatch,
+    #[msg("Claim fee overflow")]
+    FeeOverflow,
+    #[msg("Loot collection is not accepting claims")]
+    CollectionInactive,
+    #[msg("Seasonal collections need the root FIZZLOOT collection accounts")]
+    MissingParentCollection,
+    #[msg("Voucher must be claimed through the other claim instruction")]
+    WrongClaimMode,
+    #[msg("V1 vouchers can only be claimed as NFTs")]
+    LegacyVoucherNftOnly,
+    #[msg("Loot tree is full")]
+    LootTreeFull,
+    #[msg("Loot tree depth must be 1..=20")]
+    InvalidTreeDepth,
+    #[msg("Loot item is not the PDA for this voucher's loot id")]
+    LootItemAddressMismatch,
+    #[msg("Burn share must be at most 10000 bps")]
+    InvalidBurnBps,
+    #[msg("Coordinates out of range")]
+    InvalidCoordinates,
+    #[msg("Voucher geohash does not match its coordinates")]
+    GeohashMismatch,
+    #[msg("Location cell precision must be 1..=8 geohash characters")]
+    InvalidCellPrecision,
+    #[msg("Location cell epoch must be positive and cooldown non-negative")]
+    InvalidCellEpoch,
+    #[msg("Too many caches claimed in this area this epoch")]
+    CellQuotaExceeded,
+    #[msg("Player claimed in this area too recently")]
+    CellCooldownActive,
+    #[msg("Batch location cell is not the PDA for this voucher's geohash")]
+    BatchLocationCellMismatch,
+    #[msg("Batch cell cooldown is not the player's PDA for this voucher's geohash")]
+    BatchCellCooldownMismatch,
+    #[msg("Loot item does not belong to this mint")]
+    LootItemMismatch,
+    #[msg("Player does not hold this loot NFT")]
+    NotLootOwner,
+    #[msg("Salvage pool has too few CAPS for this payout")]
+    SalvagePoolEmpty,
+    #[msg("Loot name template too long (max 64)")]
+    NameTemplateTooLong,
+    #[msg("Private claims may only publish a coarse geohash")]
+    PrivateCellTooPrecise,
+    #[msg("Loot was not claimed privately")]
+    LocationNotPrivate,
+    #[msg("Revealed location does not match the commitment")]
+    LocationRevealMismatch,
+    #[msg("Only the claiming player can do this")]
+    NotLootClaimer,
+    #[msg("Claim fees can't be paid in SOL on this deployment")]
+    SolPaymentDisabled,
+    #[msg("Witness attestations need a positive max age")]
+    InvalidWitnessConfig,
+    #[msg("Not enough witness attestations for this cache")]
+    NotEnoughWitnesses,
+    #[msg("Witness is not a registered beacon or registered player")]
+    UntrustedWitness,
+    #[msg("Same witness attested twice")]
+    DuplicateWitness,
+    #[msg("Witness attestation timestamp is too old or in the future")]
+    StaleAttestation,
+    #[msg("Beacon already registered")]
+    BeaconAlreadyRegistered,
+    #[msg("Beacon registry is full")]
+    BeaconRegistryFull,
+    #[msg("Item max durability must be positive")]
+    InvalidItemDurability,
+    #[msg("Item level overflow")]
+    ItemLevelOverflow,
+    #[msg("Too many mods on this item")]
+    TooManyLootMods,
+    #[msg("Item is already at full durability")]
+    ItemNotDamaged,
+    #[msg("Core items need the asset and mpl-core program accounts")]
+    CoreAssetRequired,
+    #[msg("Fusion recipe must burn 2-10 items into a higher rarity")]
+    InvalidFusionRecipe,
+    #[msg("Fusion needs 3 remaining accounts per input item")]
+    FusionAccountsMismatch,
+    #[msg("Fusion inputs must be in ascending loot id order")]
+    FusionInputsUnsorted,
+    #[msg("Fusion inputs must share one rarity")]
+    FusionRarityMismatch,
+    #[msg("This rarity can't be fused")]
+    FusionRecipeDisabled,
+    #[msg("Wrong number of items for this fusion recipe")]
+    FusionInputCount,
+    #[msg("Fused loot id doesn't match the burned items")]
+    FusedLootIdMismatch,
+    #[msg("Voucher nonce is too far behind the player's newest nonce")]
+    VoucherNonceTooOld,
+    #[msg("This loot id has already been claimed")]
+    LootAlreadyClaimed,
+    #[msg("Loot item was salvaged or fused")]
+    LootItemBurned,
+    #[msg("Fusion can be revealed from the slot after it was committed")]
+    FusionNotRevealable,
 }
 
 #[error_code]
 pub enum FizzError {
-    #[msg("Must hold at least 1000 CAPS to launch tokens")]
+    #[msg("Not enough CAPS to launch tokens")]
     InsufficientCapsToLaunch,
     #[msg("Token has already graduated")]
     TokenGraduated,
     #[msg("Token has already graduated")]
     AlreadyGraduated,
-    #[msg("Token needs 85 SOL to graduate")]
+    #[msg("Token hasn't raised enough SOL to graduate")]
     NotReadyToGraduate,
     #[msg("Slippage exceeded")]
     SlippageExceeded,
@@ -967,4 +5840,657 @@ pub enum FizzError {
     SymbolTooLong,
     #[msg("URI too long (max 200)")]
     UriTooLong,
-}
\ No newline at end of file
+    #[msg("Math overflow")]
+    MathOverflow,
+    #[msg("Launch parameters out of range")]
+    InvalidLaunchParams,
+    #[msg("Transaction deadline has passed")]
+    DeadlineExpired,
+    #[msg("Launch tiers must be 1-4 rows with ascending min_caps and in-range perks")]
+    InvalidLaunchTiers,
+    #[msg("Launch type not allowed here")]
+    InvalidLaunchType,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compressed_claim_blocks_nft_claim_of_same_loot_id() {
+        let loot_id = 42u64;
+        let (key, _) =
+            Pubkey::find_program_address(&[LOOT_ITEM_SEEDS, &loot_id.to_le_bytes()], &crate::ID);
+        let system = anchor_lang::system_program::ID;
+        let (mut lamports, mut empty) = (0u64, Vec::new());
+        let unclaimed =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut empty, &system, false, 0);
+        check_loot_id_unclaimed(&unclaimed, loot_id).unwrap();
+        assert_eq!(
+            check_loot_id_unclaimed(&unclaimed, loot_id + 1).unwrap_err(),
+            ErrorCode::LootItemAddressMismatch.into()
+        );
+
+        // What claim_loot_compressed leaves behind for this loot id
+        let mut data = Vec::new();
+        LootItem {
+            loot_id,
+            mint: Pubkey::new_unique(),
+            roll: LootRoll::default(),
+            claimed_by: Pubkey::new_unique(),
+            claimed_at: 0,
+            location_commitment: None,
+            burned: false,
+            bump: 255,
+        }
+        .try_serialize(&mut data)
+        .unwrap();
+        let mut lamports = 1_000_000u64;
+        let claimed =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &crate::ID, false, 0);
+        // claim_loot / claim_loot_core / claim_loot_batch run the same check
+        assert_eq!(
+            check_loot_id_unclaimed(&claimed, loot_id).unwrap_err(),
+            ErrorCode::LootAlreadyClaimed.into()
+        );
+    }
+
+    fn loot_config() -> LootConfig {
+        LootConfig {
+            claim_mode: LootClaimModePolicy::VoucherChooses,
+            claim_fee: 10 * CAPS_DECIMALS,
+            burn_bps: 2_500,
+            treasury: Pubkey::new_unique(),
+            free_claims_per_day: 2,
+            cell_precision: 6,
+            cell_epoch_seconds: SECONDS_PER_DAY,
+            max_claims_per_cell: 10,
+            cell_cooldown_seconds: 0,
+            sponsored_rent_caps: 3 * CAPS_DECIMALS,
+            sol_treasury: Pubkey::new_unique(),
+            lamports_per_caps: 1_000_000,
+            witness_min_rarity: LootRarity::Legendary,
+            witnesses_required: 0,
+            witness_max_age_seconds: 0,
+            accept_player_witnesses: false,
+            item_max_durability: 100,
+            repair_caps_per_point: 0,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn fee_split_rounds_burn_down_and_sums_to_total() {
+        let mut config = loot_config();
+        assert_eq!(config.split_fee(1_001), (250, 751));
+        assert_eq!(config.split_fee(3), (0, 3));
+        assert_eq!(config.split_fee(0), (0, 0));
+        for burn_bps in [0, 1, 3_333, 9_999, 10_000] {
+            config.burn_bps = burn_bps;
+            for amount in [0, 1, 7, 10_001, u64::MAX] {
+                let (burned, to_treasury) = config.split_fee(amount);
+                assert_eq!(burned + to_treasury, amount);
+            }
+        }
+        config.burn_bps = 10_000;
+        assert_eq!(config.split_fee(u64::MAX), (u64::MAX, 0));
+    }
+
+    #[test]
+    fn claim_fee_scales_with_rarity() {
+        let mut config = loot_config();
+        assert_eq!(config.claim_fee_for(LootRarity::Common).unwrap(), 10 * CAPS_DECIMALS);
+        assert_eq!(config.claim_fee_for(LootRarity::Legendary).unwrap(), 100 * CAPS_DECIMALS);
+        config.claim_fee = 3;
+        assert_eq!(config.claim_fee_for(LootRarity::Uncommon).unwrap(), 4);
+        config.claim_fee = 0;
+        assert_eq!(config.claim_fee_for(LootRarity::Epic).unwrap(), 0);
+        config.claim_fee = u64::MAX;
+        assert_eq!(
+            config.claim_fee_for(LootRarity::Rare).unwrap_err(),
+            ErrorCode::FeeOverflow.into()
+        );
+    }
+
+    #[test]
+    fn free_daily_claims_come_first() {
+        let config = loot_config();
+        let mut player = LootPlayer {
+            player: Pubkey::new_unique(),
+            nonces: NonceWindow::default(),
+            free_claim_day: 0,
+            free_claims_used: 0,
+            bump: 255,
+        };
+        let day = 20_000 * SECONDS_PER_DAY;
+        assert_eq!(loot_fee_due(&config, &mut player, LootRarity::Rare, day).unwrap(), 0);
+        assert_eq!(loot_fee_due(&config, &mut player, LootRarity::Rare, day + 1).unwrap(), 0);
+        assert_eq!(
+            loot_fee_due(&config, &mut player, LootRarity::Rare, day + 2).unwrap(),
+            25 * CAPS_DECIMALS
+        );
+        // The allowance resets at the next UTC midnight
+        let next_day = day + SECONDS_PER_DAY;
+        assert_eq!(loot_fee_due(&config, &mut player, LootRarity::Rare, next_day).unwrap(), 0);
+        assert_eq!(player.free_claims_used, 1);
+    }
+
+    #[test]
+    fn sol_fees_and_rent_reimbursement() {
+        let mut config = loot_config();
+        // 0.001 SOL per CAPS, rounded up in the treasury's favor
+        assert_eq!(config.caps_to_lamports(CAPS_DECIMALS * 3 / 2).unwrap(), 1_500_000);
+        assert_eq!(config.caps_to_lamports(1).unwrap(), 1);
+        assert_eq!(config.caps_to_lamports(0).unwrap(), 0);
+        config.lamports_per_caps = 0;
+        assert_eq!(
+            config.caps_to_lamports(1).unwrap_err(),
+            ErrorCode::SolPaymentDisabled.into()
+        );
+
+        let (player, relayer) = (Pubkey::new_unique(), Pubkey::new_unique());
+        assert_eq!(config.rent_reimbursement(&player, &player, 5).unwrap(), 0);
+        assert_eq!(config.rent_reimbursement(&relayer, &player, 2).unwrap(), 6 * CAPS_DECIMALS);
+        assert_eq!(
+            config.rent_reimbursement(&relayer, &player, u64::MAX).unwrap_err(),
+            ErrorCode::FeeOverflow.into()
+        );
+    }
+
+    fn v1_payload(location_hint: &str) -> LootVoucherPayload {
+        LootVoucherPayload::V1(LootVoucherV1 {
+            loot_id: 7,
+            latitude: 40.7128,
+            longitude: -74.006,
+            timestamp: 0,
+            location_hint: location_hint.to_string(),
+        })
+    }
+
+    fn metadata_config(name_template: &str) -> LootMetadataConfig {
+        LootMetadataConfig {
+            name_template: name_template.to_string(),
+            private_name_template: "{rarity} Cache".to_string(),
+            symbol: "FIZZLOOT".to_string(),
+            uri_template: "https://atomicfizzcaps.xyz/loot/{id}.json".to_string(),
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn renders_template_placeholders() {
+        let payload = v1_payload("Diner");
+        assert_eq!(
+            render_loot_template("#{id} {rarity} @{lat},{lon} {hint}", &payload),
+            "#7 Common @40.7128,-74.0060 Diner"
+        );
+        assert_eq!(render_loot_template("{geohash}", &payload), payload.geohash_str());
+        // Player-visible hint text is inserted last and never expanded
+        assert_eq!(render_loot_template("{hint}", &v1_payload("{id}")), "{id}");
+        assert_eq!(
+            render_fused_template("{rarity} #{id} {geohash} {hint}", 3, LootRarity::Epic),
+            "Epic #3 ? Fused"
+        );
+    }
+
+    #[test]
+    fn rendered_names_fit_metaplex_limits() {
+        let config = metadata_config("Loot #{id} found at {hint}");
+        let name = config.render_name(&v1_payload("the old Red Rocket station"));
+        assert_eq!(name, "Loot #7 found at the old Red Roc");
+
+        // Never split a multi-byte character
+        let name = config.render_name(&v1_payload("cafés ☢☢☢☢"));
+        assert_eq!(name, "Loot #7 found at cafés ☢☢");
+        assert_eq!(name.len(), 30);
+
+        let config = metadata_config(&"{rarity}".repeat(MAX_NAME_TEMPLATE_LEN / 8));
+        assert_eq!(config.render_name(&v1_payload("")).len(), MAX_LOOT_NAME_LEN);
+        assert_eq!(config.render_fused_name(1, LootRarity::Legendary), "Legendary Cache");
+
+        let long_hint = "x".repeat(300);
+        let config = LootMetadataConfig {
+            uri_template: "https://atomicfizzcaps.xyz/{hint}".to_string(),
+            ..metadata_config("")
+        };
+        assert_eq!(config.render_uri(&v1_payload(&long_hint)).len(), MAX_LOOT_URI_LEN);
+        assert_eq!(truncate_utf8("short".to_string(), MAX_LOOT_NAME_LEN), "short");
+    }
+
+    #[test]
+    fn nonces_redeem_out_of_order_once() {
+        let mut window = NonceWindow::default();
+        window.consume(6).unwrap();
+        for nonce in [2, 5, 1, 3, 4] {
+            window.consume(nonce).unwrap();
+        }
+        for nonce in [0, 1, 4, 6] {
+            assert_eq!(window.consume(nonce).unwrap_err(), ErrorCode::VoucherNonceUsed.into());
+        }
+        window.consume(7).unwrap();
+        assert_eq!(window.highest, 7);
+    }
+
+    #[test]
+    fn nonce_window_slides() {
+        let mut window = NonceWindow::default();
+        window.consume(10).unwrap();
+        // Jump across a word boundary; 10 must still read as used
+        window.consume(10 + 70).unwrap();
+        assert_eq!(window.consume(10).unwrap_err(), ErrorCode::VoucherNonceUsed.into());
+        window.consume(11).unwrap();
+
+        window.consume(80 + NONCE_WINDOW - 1).unwrap();
+        assert_eq!(window.consume(80).unwrap_err(), ErrorCode::VoucherNonceUsed.into());
+        assert_eq!(window.consume(79).unwrap_err(), ErrorCode::VoucherNonceTooOld.into());
+
+        // A jump past the whole window forgets everything behind it
+        window.consume(10_000).unwrap();
+        assert_eq!(window.used, [1, 0, 0, 0]);
+        window.consume(10_000 - NONCE_WINDOW + 1).unwrap();
+    }
+
+    #[test]
+    fn only_registered_player_witnesses_count() {
+        let beacon = Pubkey::new_unique();
+        let player = Pubkey::new_unique();
+        let claimant = Pubkey::new_unique();
+        let beacons = BeaconRegistry { beacons: vec![beacon], bump: 255 };
+        let mut config = loot_config();
+
+        let registration = LootWitness { witness: player, bump: 255 };
+        let mut data = Vec::new();
+        registration.try_serialize(&mut data).unwrap();
+        let key = Pubkey::new_unique();
+        let mut lamports = 0;
+        let owner = crate::ID;
+        let account = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &owner, false, 0);
+        let registered = registered_witnesses(std::slice::from_ref(&account)).unwrap();
+        assert_eq!(registered, [player]);
+
+        // Off by default: only beacons count
+        assert!(witness_trusted(&config, &beacons, &registered, &[claimant], &beacon));
+        assert!(!witness_trusted(&config, &beacons, &registered, &[claimant], &player));
+
+        config.accept_player_witnesses = true;
+        assert!(witness_trusted(&config, &beacons, &registered, &[claimant], &player));
+        // A key the claimant made up, or the claimant itself, never counts
+        let made_up = Pubkey::new_unique();
+        assert!(!witness_trusted(&config, &beacons, &registered, &[claimant], &made_up));
+        assert!(!witness_trusted(&config, &beacons, &[claimant], &[claimant], &claimant));
+
+        // A registration not owned by the program is rejected
+        let (mut lamports, mut data) = (0, account.try_borrow_data().unwrap().to_vec());
+        let forged = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &key, false, 0);
+        assert!(registered_witnesses(&[forged]).is_err());
+    }
+
+    fn weapon(damage: u16, armor: u16, weight: u16, caps_value: u32) -> LootRoll {
+        LootRoll {
+            rarity: LootRarity::Rare,
+            category: LootCategory::Weapon,
+            stats: LootStats { damage, armor, weight, caps_value },
+        }
+    }
+
+    #[test]
+    fn fused_loot_ids_are_deterministic() {
+        let id = fused_loot_id_for(&[3, 9, 12]);
+        assert_eq!(id, fused_loot_id_for(&[3, 9, 12]));
+        assert_ne!(id & FUSED_LOOT_ID_FLAG, 0);
+        assert_ne!(id, fused_loot_id_for(&[3, 9, 13]));
+        assert_ne!(id, fused_loot_id_for(&[3, 9]));
+        // Order matters, which is why fuse_loot requires ascending loot ids
+        assert_ne!(id, fused_loot_id_for(&[12, 9, 3]));
+    }
+
+    #[test]
+    fn fuse_roll_keeps_the_best_stats_in_any_order() {
+        let a = weapon(100, 10, 5, 1_000);
+        let b = weapon(40, 80, 9, 3_000);
+        let mut seed = [0u8; 32];
+        // Boosts: damage +25% (max), armor +0% (26 wraps to 0), caps +21% (255 % 26)
+        seed[..4].copy_from_slice(&[3, 25, 26, 255]);
+
+        let fused = fuse_roll(&[a, b], LootRarity::Epic, &seed);
+        assert_eq!(fused, fuse_roll(&[b, a], LootRarity::Epic, &seed));
+        assert_eq!(fused.rarity, LootRarity::Epic);
+        assert_eq!(fused.category, LootCategory::Weapon);
+        assert_eq!(fused.stats, LootStats { damage: 125, armor: 80, weight: 9, caps_value: 3_630 });
+
+        // Mixed categories are picked by the seed, and boosts saturate
+        let armor = LootRoll { category: LootCategory::Armor, ..weapon(u16::MAX, 0, 0, 0) };
+        let fused = fuse_roll(&[a, armor], LootRarity::Epic, &seed);
+        assert_eq!(fused, fuse_roll(&[armor, a], LootRarity::Epic, &seed));
+        assert_eq!(fused.category, LootCategory::Aid);
+        assert_eq!(fused.stats.damage, u16::MAX);
+    }
+
+    #[test]
+    fn fusion_recipes_only_upgrade_rarity() {
+        let recipe = |inputs, output| FusionRecipe { inputs, output };
+        let mut recipes = [recipe(0, LootRarity::Common); LootRarity::COUNT];
+        recipes[LootRarity::Common as usize] = recipe(MAX_FUSION_INPUTS, LootRarity::Uncommon);
+        recipes[LootRarity::Epic as usize] = recipe(2, LootRarity::Legendary);
+        let mut table = FusionRecipes { recipes, bump: 255 };
+        table.apply(recipes).unwrap();
+        assert_eq!(table.recipe_for(LootRarity::Legendary).inputs, 0);
+
+        let invalid = [
+            (LootRarity::Legendary, recipe(2, LootRarity::Legendary)),
+            (LootRarity::Rare, recipe(2, LootRarity::Rare)),
+            (LootRarity::Rare, recipe(2, LootRarity::Uncommon)),
+            (LootRarity::Rare, recipe(1, LootRarity::Epic)),
+            (LootRarity::Rare, recipe(MAX_FUSION_INPUTS + 1, LootRarity::Epic)),
+        ];
+        for (input, bad) in invalid {
+            let mut bad_recipes = recipes;
+            bad_recipes[input as usize] = bad;
+            assert_eq!(
+                table.apply(bad_recipes).unwrap_err(),
+                ErrorCode::InvalidFusionRecipe.into()
+            );
+        }
+    }
+
+    fn launched_curve() -> FizzBondingCurve {
+        FizzBondingCurve {
+            creator: Pubkey::new_unique(),
+            token_mint: Pubkey::new_unique(),
+            sol_reserve: 0,
+            token_reserve: FizzLaunchParams::DEFAULT.curve_supply(CREATOR_BONUS_BPS).unwrap(),
+            graduated: false,
+            created_at: 0,
+            launch_type: FizzLaunchType::AdminFree,
+            virtual_sol: VIRTUAL_SOL,
+            fee_bps: FEE_BPS,
+            graduation_sol: GRADUATION_SOL,
+            creator_bonus_bps: CREATOR_BONUS_BPS,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn sell_books_the_vault_payout() {
+        let mut curve = launched_curve();
+        let supply = curve.token_reserve;
+        curve.sol_reserve = 5_000_000_000;
+        curve.token_reserve = supply / 2;
+
+        let quote = curve.sell(supply / 100).unwrap();
+        // Seller payout plus treasury fee is exactly what leaves the vault
+        assert_eq!(quote.sol_out + quote.fee, quote.sol_out_gross);
+        assert_eq!(quote.fee, curve_math::fee(quote.sol_out_gross, FEE_BPS).unwrap());
+        assert_eq!(curve.sol_reserve, 5_000_000_000 - quote.sol_out_gross);
+        assert_eq!(curve.token_reserve, supply / 2 + supply / 100);
+
+        // Never pays out more than buyers deposited
+        let deposited = curve.sol_reserve;
+        let quote = curve.sell(supply).unwrap();
+        assert_eq!(quote.sol_out_gross, deposited);
+        assert_eq!(curve.sol_reserve, 0);
+
+        assert_eq!(curve.sell(0).unwrap_err(), FizzError::ZeroAmount.into());
+        curve.graduated = true;
+        assert_eq!(curve.sell(1).unwrap_err(), FizzError::TokenGraduated.into());
+    }
+
+    fn fizz_config(launch_tiers: Vec<FizzLaunchTier>) -> FizzConfig {
+        FizzConfig {
+            authority: Pubkey::new_unique(),
+            treasury: Pubkey::new_unique(),
+            caps_mint: Pubkey::new_unique(),
+            total_tokens_launched: 0,
+            total_volume_sol: 0,
+            total_caps_burned: 0,
+            admin_usdc_launches: 0,
+            params: FizzLaunchParams::DEFAULT,
+            launch_tiers,
+            bump: 255,
+        }
+    }
+
+    #[test]
+    fn fizz_accounts_fit_their_space() {
+        let config = fizz_config(vec![FizzLaunchTier::defaults()[0]; MAX_LAUNCH_TIERS]);
+        let mut data = Vec::new();
+        config.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), FizzConfig::SPACE);
+
+        let mut data = Vec::new();
+        launched_curve().try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), FizzBondingCurve::SPACE);
+    }
+
+    #[test]
+    fn launch_tiers_match_on_their_boundaries() {
+        let config = fizz_config(FizzLaunchTier::defaults());
+        let tier_at = |balance| config.launch_tier_for(balance).map(|tier| tier.launch_type);
+        assert_eq!(tier_at(0), None);
+        assert_eq!(tier_at(CAPS_TO_LAUNCH - 1), None);
+        assert_eq!(tier_at(CAPS_TO_LAUNCH), Some(FizzLaunchType::CapsStandard));
+        assert_eq!(tier_at(CAPS_VETERAN_THRESHOLD - 1), Some(FizzLaunchType::CapsStandard));
+        assert_eq!(tier_at(CAPS_VETERAN_THRESHOLD), Some(FizzLaunchType::CapsVeteran));
+        assert_eq!(tier_at(u64::MAX), Some(FizzLaunchType::CapsVeteran));
+    }
+
+    #[test]
+    fn launch_tier_tables_must_be_sorted_and_distinct() {
+        let [standard, veteran] = FizzLaunchTier::defaults()[..] else { unreachable!() };
+        FizzLaunchTier::validate_table(&[standard, veteran]).unwrap();
+
+        let overlapping = FizzLaunchTier { min_caps: standard.min_caps, ..veteran };
+        let invalid = [
+            vec![],
+            vec![veteran, standard],
+            vec![standard, overlapping],
+            vec![standard; MAX_LAUNCH_TIERS + 1],
+            vec![FizzLaunchTier { launch_fee: standard.min_caps + 1, ..standard }],
+            vec![FizzLaunchTier { fee_bps: MAX_FEE_BPS + 1, ..standard }],
+            vec![FizzLaunchTier { creator_bonus_bps: MAX_CREATOR_BONUS_BPS + 1, ..standard }],
+        ];
+        for tiers in invalid {
+            assert_eq!(
+                FizzLaunchTier::validate_table(&tiers).unwrap_err(),
+                FizzError::InvalidLaunchTiers.into()
+            );
+        }
+        let admin = FizzLaunchTier { launch_type: FizzLaunchType::AdminFree, ..standard };
+        assert_eq!(
+            FizzLaunchTier::validate_table(&[admin]).unwrap_err(),
+            FizzError::InvalidLaunchType.into()
+        );
+    }
+
+    #[test]
+    fn curves_record_their_launch_tier() {
+        let params = FizzLaunchParams {
+            admin_fee_bps: 250,
+            admin_creator_bonus_bps: 0,
+            graduation_sol: 42_000_000_000,
+            ..FizzLaunchParams::DEFAULT
+        };
+        let veteran = FizzLaunchTier {
+            fee_bps: 50,
+            creator_bonus_bps: 1_000,
+            ..FizzLaunchTier::defaults()[1]
+        };
+
+        let mut curve = launched_curve();
+        curve.snapshot(&params);
+        assert_eq!((curve.fee_bps, curve.creator_bonus_bps), (250, 0));
+
+        // CAPS launches ignore the admin fee and bonus
+        curve.apply_tier(&veteran);
+        assert_eq!(curve.launch_type, FizzLaunchType::CapsVeteran);
+        assert_eq!((curve.fee_bps, curve.creator_bonus_bps), (50, 1_000));
+        assert_eq!(curve.graduation_sol, 42_000_000_000);
+        assert_eq!(curve.virtual_sol, VIRTUAL_SOL);
+    }
+
+    #[test]
+    fn v1_vouchers_are_nft_only() {
+        let payload = v1_payload("Diner");
+        let mut config = loot_config();
+        for policy in [LootClaimModePolicy::VoucherChooses, LootClaimModePolicy::NftOnly] {
+            config.claim_mode = policy;
+            assert_eq!(config.resolve_claim_mode(&payload).unwrap(), LootClaimMode::Nft);
+        }
+        for policy in [LootClaimModePolicy::CompressedOnly, LootClaimModePolicy::CoreOnly] {
+            config.claim_mode = policy;
+            assert_eq!(
+                config.resolve_claim_mode(&payload).unwrap_err(),
+                ErrorCode::LegacyVoucherNftOnly.into()
+            );
+        }
+    }
+
+    #[test]
+    fn salvaged_loot_id_stays_claimed() {
+        let loot_id = 43u64;
+        let (key, _) =
+            Pubkey::find_program_address(&[LOOT_ITEM_SEEDS, &loot_id.to_le_bytes()], &crate::ID);
+        let mut item = LootItem {
+            loot_id,
+            mint: Pubkey::new_unique(),
+            roll: LootRoll::default(),
+            claimed_by: Pubkey::new_unique(),
+            claimed_at: 0,
+            location_commitment: None,
+            burned: false,
+            bump: 255,
+        };
+
+        // Claimed as an NFT, then salvaged (fuse_loot burns inputs the same way)
+        item.burn().unwrap();
+        assert_eq!(item.burn().unwrap_err(), ErrorCode::LootItemBurned.into());
+        let mut data = Vec::new();
+        item.try_serialize(&mut data).unwrap();
+        assert!(data.len() <= LOOT_ITEM_SPACE);
+
+        // The tombstone still blocks a Core or compressed claim of the same voucher
+        let mut lamports = 1_000_000u64;
+        let owner = crate::ID;
+        let tombstone =
+            AccountInfo::new(&key, false, true, &mut lamports, &mut data, &owner, false, 0);
+        assert_eq!(
+            check_loot_id_unclaimed(&tombstone, loot_id).unwrap_err(),
+            ErrorCode::LootAlreadyClaimed.into()
+        );
+    }
+
+    #[test]
+    fn compressed_items_get_their_own_state() {
+        // Two compressed items from one tree share `LootItem.mint`
+        let tree = Pubkey::new_unique();
+        let item = |loot_id| LootItem {
+            loot_id,
+            mint: tree,
+            roll: LootRoll::default(),
+            claimed_by: Pubkey::new_unique(),
+            claimed_at: 0,
+            location_commitment: None,
+            burned: false,
+            bump: 255,
+        };
+        // Same seeds as InitItemState, ApplyItemWear and RepairItem
+        let state_address = |item: &LootItem| {
+            Pubkey::find_program_address(
+                &[LOOT_ITEM_STATE_SEEDS, item.loot_id.to_le_bytes().as_ref()],
+                &crate::ID,
+            )
+            .0
+        };
+        assert_ne!(state_address(&item(1)), state_address(&item(2)));
+    }
+
+    #[test]
+    fn sol_vault_stays_rent_exempt() {
+        let rent = Rent::default();
+        let floor = rent.minimum_balance(0);
+        assert_eq!(sol_vault_rent_top_up(&rent, 0), floor);
+        assert_eq!(sol_vault_rent_top_up(&rent, floor + 5), 0);
+
+        // Launch funds the floor; a first buy far below it still lands on top
+        let mut vault = sol_vault_rent_top_up(&rent, 0);
+        let mut curve = launched_curve();
+        let buy = curve_math::quote_buy(curve.reserves(), 10_000, FEE_BPS).unwrap();
+        curve.sol_reserve += buy.sol_in;
+        curve.token_reserve -= buy.tokens_out;
+        vault += buy.sol_in;
+
+        // A partial sell leaves a few lamports of reserve behind
+        let quote = curve.sell(buy.tokens_out / 2).unwrap();
+        vault -= quote.sol_out_gross;
+        assert!(curve.sol_reserve > 0 && curve.sol_reserve < floor);
+        assert_eq!(vault, floor + curve.sol_reserve);
+
+        let quote = curve.sell(buy.tokens_out - buy.tokens_out / 2).unwrap();
+        vault -= quote.sol_out_gross;
+        assert_eq!(vault, floor + curve.sol_reserve);
+        assert!(vault >= floor);
+    }
+
+    #[test]
+    fn curve_supply_leaves_tokens_for_the_pool() {
+        let params = FizzLaunchParams::DEFAULT;
+        params.validate().unwrap();
+        for creator_bonus_bps in [0, CREATOR_BONUS_BPS, MAX_CREATOR_BONUS_BPS] {
+            let supply = params.curve_supply(creator_bonus_bps).unwrap();
+            assert!(supply <= TOTAL_SUPPLY / 10 * 9);
+        }
+        // So little virtual SOL that matching the closing price would leave the
+        // pool almost no tokens
+        let thin = FizzLaunchParams { virtual_sol: 1_000_000, ..params };
+        assert_eq!(thin.validate().unwrap_err(), FizzError::InvalidLaunchParams.into());
+        assert_eq!(
+            params.curve_supply(curve_math::BPS_DENOMINATOR + 1).unwrap_err(),
+            FizzError::InvalidLaunchParams.into()
+        );
+    }
+
+    #[test]
+    fn fusion_rolls_from_the_commit_slot_hash() {
+        // SlotHashes layout: count, then (slot, hash) pairs newest first
+        let mut data = 3u64.to_le_bytes().to_vec();
+        for (slot, byte) in [(105u64, 5u8), (104, 4), (102, 2)] {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[byte; 32]);
+        }
+        assert_eq!(slot_hash_at(&data, 104), Some([4; 32]));
+        assert_eq!(slot_hash_at(&data, 102), Some([2; 32]));
+        assert_eq!(slot_hash_at(&data, 103), None);
+        assert_eq!(slot_hash_at(&data, 101), None);
+        assert_eq!(slot_hash_at(&data[..4], 104), None);
+
+        let id = fused_loot_id_for(&[3, 9]);
+        let seed = fusion_seed(id, Some([4; 32]));
+        assert_ne!(seed, fusion_seed(id, Some([5; 32])));
+        assert_ne!(seed, fusion_seed(id + 1, Some([4; 32])));
+
+        // An aged-out commit slot rolls the floor: no boosts, first category
+        let a = weapon(100, 10, 5, 1_000);
+        let armor = LootRoll { category: LootCategory::Armor, ..weapon(40, 80, 9, 3_000) };
+        let floor = fuse_roll(&[a, armor], LootRarity::Epic, &fusion_seed(id, None));
+        assert_eq!(floor.category, LootCategory::Junk);
+        assert_eq!(floor.stats, LootStats { damage: 100, armor: 80, weight: 9, caps_value: 3_000 });
+    }
+
+    #[test]
+    fn pending_fusion_fits_its_space() {
+        let max = MAX_FUSION_INPUTS as u64;
+        let pending = PendingFusion {
+            player: Pubkey::new_unique(),
+            fused_loot_id: fused_loot_id_for(&(0..max).collect::<Vec<_>>()),
+            burned_ids: (0..max).collect(),
+            input_rarity: LootRarity::Rare,
+            output: LootRarity::Epic,
+            rolls: vec![weapon(1, 1, 1, 1); MAX_FUSION_INPUTS as usize],
+            commit_slot: u64::MAX,
+            bump: 255,
+        };
+        let mut data = Vec::new();
+        pending.try_serialize(&mut data).unwrap();
+        assert_eq!(data.len(), PENDING_FUSION_SPACE);
+    }
+}
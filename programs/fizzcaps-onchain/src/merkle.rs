@@ -0,0 +1,196 @@
+// ============ APPEND-ONLY MERKLE TREE ============
+//
+// Incremental merkle tree for compressed loot. Only the right-most branch is
+// stored on-chain (one node per level), so appends cost `depth` hashes and the
+// account size doesn't grow with the number of leaves. Recent roots are kept
+// in a small ring buffer so proofs built against a root that was current a few
+// appends ago can still be matched, the same trick Bubblegum's concurrent trees
+// use. No instruction takes an inclusion proof yet; indexers verify leaves
+// off-chain against the roots and the `CompressedLootMinted` events.
+//
+// Nodes are sha256(left || right); empty subtrees hash to `zero_hash(level)`.
+
+use anchor_lang::prelude::*;
+use solana_sha256_hasher::hashv;
+
+/// Deepest tree we allocate space for (2^20 = ~1M leaves)
+pub const MAX_TREE_DEPTH: usize = 20;
+/// Roots kept for proofs built against a slightly stale tree
+pub const ROOT_HISTORY: usize = 8;
+
+pub type Node = [u8; 32];
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTree {
+    pub depth: u8,
+    pub leaf_count: u64,
+    /// Left siblings along the path of the next append, by level
+    pub branch: [Node; MAX_TREE_DEPTH],
+    pub roots: [Node; ROOT_HISTORY],
+    /// Index of the current root in `roots`
+    pub root_index: u8,
+}
+
+impl MerkleTree {
+    pub const SIZE: usize = 1 + 8 + 32 * MAX_TREE_DEPTH + 32 * ROOT_HISTORY + 1;
+
+    pub fn new(depth: u8) -> Option<Self> {
+        if depth == 0 || depth as usize > MAX_TREE_DEPTH {
+            return None;
+        }
+        Some(Self {
+            depth,
+            leaf_count: 0,
+            branch: [[0u8; 32]; MAX_TREE_DEPTH],
+            roots: [zero_hash(depth as usize); ROOT_HISTORY],
+            root_index: 0,
+        })
+    }
+
+    pub fn capacity(&self) -> u64 {
+        1u64 << self.depth
+    }
+
+    pub fn root(&self) -> Node {
+        self.roots[self.root_index as usize]
+    }
+
+    /// True if `root` is the current root or one of the recent ones
+    pub fn is_known_root(&self, root: &Node) -> bool {
+        self.roots.iter().any(|r| r == root)
+    }
+
+    /// Append a leaf, returning its index, or `None` if the tree is full
+    pub fn append(&mut self, leaf: Node) -> Option<u64> {
+        if self.leaf_count >= self.capacity() {
+            return None;
+        }
+        let index = self.leaf_count;
+
+        // Fold the new leaf up until it becomes a left child. If it never
+        // does, the tree is now full and the folded node is the root.
+        let mut node = leaf;
+        let mut size = index + 1;
+        let mut full = true;
+        for level in 0..self.depth as usize {
+            if size & 1 == 1 {
+                self.branch[level] = node;
+                full = false;
+                break;
+            }
+            node = hash_pair(&self.branch[level], &node);
+            size >>= 1;
+        }
+        self.leaf_count += 1;
+
+        let root = if full { node } else { self.compute_root() };
+        self.root_index = ((self.root_index as usize + 1) % ROOT_HISTORY) as u8;
+        self.roots[self.root_index as usize] = root;
+        Some(index)
+    }
+
+    fn compute_root(&self) -> Node {
+        let mut node = [0u8; 32];
+        let mut zero = [0u8; 32];
+        let mut size = self.leaf_count;
+        for level in 0..self.depth as usize {
+            node = if size & 1 == 1 {
+                hash_pair(&self.branch[level], &node)
+            } else {
+                hash_pair(&node, &zero)
+            };
+            zero = hash_pair(&zero, &zero);
+            size >>= 1;
+        }
+        node
+    }
+}
+
+pub fn hash_pair(left: &Node, right: &Node) -> Node {
+    hashv(&[left, right]).to_bytes()
+}
+
+/// Root of an empty subtree of the given height
+pub fn zero_hash(level: usize) -> Node {
+    (0..level).fold([0u8; 32], |node, _| hash_pair(&node, &node))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(i: u8) -> Node {
+        hashv(&[&[i]]).to_bytes()
+    }
+
+    /// Root implied by an inclusion proof (siblings from the leaf upwards)
+    fn proof_root(leaf: &Node, index: u64, proof: &[Node]) -> Node {
+        proof.iter().enumerate().fold(*leaf, |node, (level, sibling)| {
+            if (index >> level) & 1 == 0 {
+                hash_pair(&node, sibling)
+            } else {
+                hash_pair(sibling, &node)
+            }
+        })
+    }
+
+    /// Build the whole tree level by level; returns all levels (leaves first)
+    fn full_tree(leaves: &[Node], depth: usize) -> Vec<Vec<Node>> {
+        let mut level: Vec<Node> = (0..1usize << depth)
+            .map(|i| leaves.get(i).copied().unwrap_or([0u8; 32]))
+            .collect();
+        let mut levels = vec![level.clone()];
+        for _ in 0..depth {
+            level = level.chunks(2).map(|p| hash_pair(&p[0], &p[1])).collect();
+            levels.push(level.clone());
+        }
+        levels
+    }
+
+    #[test]
+    fn empty_root_is_zero_hash() {
+        let tree = MerkleTree::new(4).unwrap();
+        assert_eq!(tree.root(), full_tree(&[], 4)[4][0]);
+    }
+
+    #[test]
+    fn incremental_root_matches_full_tree() {
+        let depth = 4;
+        let mut tree = MerkleTree::new(depth as u8).unwrap();
+        let mut leaves = Vec::new();
+        for i in 0..16u8 {
+            leaves.push(leaf(i));
+            assert_eq!(tree.append(leaf(i)), Some(i as u64));
+            assert_eq!(tree.root(), full_tree(&leaves, depth)[depth][0], "after {} leaves", i + 1);
+        }
+        assert_eq!(tree.append(leaf(16)), None);
+    }
+
+    #[test]
+    fn proofs_verify_against_recent_roots() {
+        let depth = 3;
+        let mut tree = MerkleTree::new(depth as u8).unwrap();
+        let leaves: Vec<Node> = (0..5u8).map(leaf).collect();
+        for l in &leaves {
+            tree.append(*l);
+        }
+        let levels = full_tree(&leaves, depth);
+        let root = tree.root();
+
+        for (i, l) in leaves.iter().enumerate() {
+            let proof: Vec<Node> = (0..depth).map(|h| levels[h][(i >> h) ^ 1]).collect();
+            assert_eq!(proof_root(l, i as u64, &proof), root);
+            assert_ne!(proof_root(&leaf(99), i as u64, &proof), root);
+        }
+
+        tree.append(leaf(5));
+        assert!(tree.is_known_root(&root));
+        assert_ne!(tree.root(), root);
+    }
+
+    #[test]
+    fn rejects_bad_depth() {
+        assert!(MerkleTree::new(0).is_none());
+        assert!(MerkleTree::new(MAX_TREE_DEPTH as u8 + 1).is_none());
+    }
+}